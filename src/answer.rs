@@ -0,0 +1,168 @@
+use crate::error::{column_of, PuzzleError, Result};
+use std::fmt;
+
+/// The answer to one part of a puzzle
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Answer {
+    Integer(usize),
+    Text(String),
+    /// Rows of characters that spell out the answer when printed
+    Grid(Vec<String>),
+}
+
+impl Answer {
+    pub fn grid(rendered: &str) -> Answer {
+        Answer::Grid(rendered.lines().map(String::from).collect())
+    }
+
+    /// Serialize the answer as a single line that `Answer::deserialize` can
+    /// read back, e.g. `int:42`, `text:abc` or `grid:#_\n_#`
+    pub fn serialize(&self) -> String {
+        match self {
+            Answer::Integer(n) => format!("int:{}", n),
+            Answer::Text(text) => format!("text:{}", escape(text)),
+            Answer::Grid(rows) => format!("grid:{}", escape(&rows.join("\n"))),
+        }
+    }
+
+    /// Errors are positioned as if `s` were the first line of the input
+    pub fn deserialize(s: &str) -> Result<Answer> {
+        let (kind, value) = s.split_once(':').ok_or_else(|| {
+            PuzzleError::parse(1, 1, format!("Answer {:?} is missing its kind", s))
+        })?;
+        let unescaped = || unescape(value).map_err(|e| e.shift(1, column_of(s, value)));
+
+        match kind {
+            "int" => value
+                .parse()
+                .map(Answer::Integer)
+                .map_err(|e| PuzzleError::at(0, s, value, e)),
+            "text" => Ok(Answer::Text(unescaped()?)),
+            "grid" => Ok(Answer::grid(&unescaped()?)),
+            _ => Err(PuzzleError::at(
+                0,
+                s,
+                kind,
+                format!("Unknown answer kind {:?}", kind),
+            )),
+        }
+    }
+}
+
+fn escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('\n', "\\n")
+}
+
+fn unescape(s: &str) -> Result<String> {
+    let mut unescaped = String::with_capacity(s.len());
+    let mut chars = s.chars().enumerate();
+
+    while let Some((i, c)) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+
+        match chars.next().map(|(_, c)| c) {
+            Some('n') => unescaped.push('\n'),
+            Some('\\') => unescaped.push('\\'),
+            Some(other) => {
+                return Err(PuzzleError::parse(
+                    1,
+                    i + 1,
+                    format!("Invalid escape sequence \\{}", other),
+                ))
+            }
+            None => {
+                return Err(PuzzleError::parse(
+                    1,
+                    i + 1,
+                    "Answer ends in a trailing backslash",
+                ))
+            }
+        }
+    }
+
+    Ok(unescaped)
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Answer::Integer(n) => write!(f, "{}", n),
+            Answer::Text(text) => write!(f, "{}", text),
+            Answer::Grid(rows) => write!(f, "{}", rows.join("\n")),
+        }
+    }
+}
+
+impl From<usize> for Answer {
+    fn from(n: usize) -> Self {
+        Answer::Integer(n)
+    }
+}
+
+impl From<String> for Answer {
+    fn from(text: String) -> Self {
+        Answer::Text(text)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(text: &str) -> Self {
+        Answer::Text(text.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display() -> Result<()> {
+        assert_eq!(Answer::from(42).to_string(), "42");
+        assert_eq!(Answer::from("abc").to_string(), "abc");
+        assert_eq!(Answer::grid("#_\n_#\n").to_string(), "#_\n_#");
+        Ok(())
+    }
+
+    #[test]
+    fn test_serialize() -> Result<()> {
+        let answers = [
+            Answer::Integer(3692219987038),
+            Answer::Text(String::from("back\\slash")),
+            Answer::grid("###_\n#__#\n"),
+        ];
+
+        assert_eq!(answers[0].serialize(), "int:3692219987038");
+        assert_eq!(answers[1].serialize(), "text:back\\\\slash");
+        assert_eq!(answers[2].serialize(), "grid:###_\\n#__#");
+
+        for answer in answers {
+            assert_eq!(Answer::deserialize(&answer.serialize())?, answer);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_deserialize_invalid() -> Result<()> {
+        assert!(Answer::deserialize("42").is_err());
+        assert!(Answer::deserialize("int:abc").is_err());
+        assert!(Answer::deserialize("float:1.5").is_err());
+        assert!(Answer::deserialize("text:a\\b").is_err());
+
+        let e = Answer::deserialize("int:abc").unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            "line 1, column 5: invalid digit found in string"
+        );
+        let e = Answer::deserialize("grid:#_\\x").unwrap_err();
+        assert!(matches!(e, PuzzleError::Parse { column: 8, .. }));
+        let e = Answer::deserialize("text:ab\\").unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            "line 1, column 8: Answer ends in a trailing backslash"
+        );
+        Ok(())
+    }
+}
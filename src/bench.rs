@@ -0,0 +1,107 @@
+use anyhow::{anyhow, Result};
+use std::time::{Duration, Instant};
+
+/// Summary statistics over repeated runs of the same code
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stats {
+    pub runs: usize,
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub p95: Duration,
+}
+
+impl Stats {
+    pub fn from_samples(samples: &[Duration]) -> Option<Stats> {
+        if samples.is_empty() {
+            return None;
+        }
+
+        let mut sorted = samples.to_vec();
+        sorted.sort();
+
+        let runs = sorted.len();
+        let total: Duration = sorted.iter().sum();
+
+        // Nearest-rank percentile, so p95 of a handful of runs is the slowest one
+        let rank = (runs * 95).div_ceil(100);
+
+        let middle = runs / 2;
+        let median = if runs.is_multiple_of(2) {
+            (sorted[middle - 1] + sorted[middle]) / 2
+        } else {
+            sorted[middle]
+        };
+
+        Some(Stats {
+            runs,
+            min: sorted[0],
+            median,
+            mean: total / runs as u32,
+            p95: sorted[rank - 1],
+        })
+    }
+}
+
+/// Call `f` `warmup` times without measuring, then time `runs` further calls
+pub fn measure<T>(warmup: usize, runs: usize, mut f: impl FnMut() -> Result<T>) -> Result<Stats> {
+    for _ in 0..warmup {
+        f()?;
+    }
+
+    let mut samples = Vec::with_capacity(runs);
+    for _ in 0..runs {
+        let start = Instant::now();
+        f()?;
+        samples.push(start.elapsed());
+    }
+
+    Stats::from_samples(&samples).ok_or_else(|| anyhow!("Need at least one run to benchmark"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(n: u64) -> Duration {
+        Duration::from_millis(n)
+    }
+
+    #[test]
+    fn test_stats() -> Result<()> {
+        let samples = (1..=20).rev().map(ms).collect::<Vec<_>>();
+        let stats = Stats::from_samples(&samples).unwrap();
+
+        assert_eq!(stats.runs, 20);
+        assert_eq!(stats.min, ms(1));
+        assert_eq!(stats.median, Duration::from_micros(10500));
+        assert_eq!(stats.mean, Duration::from_micros(10500));
+        assert_eq!(stats.p95, ms(19));
+        Ok(())
+    }
+
+    #[test]
+    fn test_stats_single() -> Result<()> {
+        let stats = Stats::from_samples(&[ms(3)]).unwrap();
+
+        assert_eq!(stats.min, ms(3));
+        assert_eq!(stats.median, ms(3));
+        assert_eq!(stats.p95, ms(3));
+        assert_eq!(Stats::from_samples(&[]), None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_measure() -> Result<()> {
+        let mut calls = 0;
+        let stats = measure(2, 5, || {
+            calls += 1;
+            Ok(())
+        })?;
+
+        assert_eq!(calls, 7);
+        assert_eq!(stats.runs, 5);
+        assert!(measure(0, 0, || Ok(())).is_err());
+        Ok(())
+    }
+}
@@ -1,30 +1,44 @@
-use anyhow::Result;
-use std::fs;
-use std::path::Path;
+use crate::error::Result;
+use crate::{parse, Answer, Puzzle};
 
-fn parse_line(line: &str) -> usize {
-    line.parse::<usize>().expect("Failed to parse")
+pub fn parse(content: &str) -> Result<Vec<usize>> {
+    parse::each_line(content)
 }
 
-fn count_depth_increase(window: usize, content: &str) -> usize {
-    let left = content.lines().map(parse_line);
-    let right = content.lines().skip(window).map(parse_line);
+fn count_depth_increase(window: usize, depths: &[usize]) -> usize {
+    let left = depths.iter();
+    let right = depths.iter().skip(window);
 
     left.zip(right).filter(|(l, r)| r > l).count()
 }
 
-fn part_one(content: &str) -> usize {
-    count_depth_increase(1, content)
+pub fn part_one(depths: &[usize]) -> usize {
+    count_depth_increase(1, depths)
 }
 
-fn part_two(content: &str) -> usize {
-    count_depth_increase(3, content)
+pub fn part_two(depths: &[usize]) -> usize {
+    count_depth_increase(3, depths)
 }
 
-pub fn main(path: &Path) -> Result<(usize, Option<usize>)> {
-    let content = fs::read_to_string(path).expect("Failed to read");
+pub struct Day1;
 
-    Ok((part_one(&content), Some(part_two(&content))))
+impl Puzzle for Day1 {
+    type Input = Vec<usize>;
+
+    const DAY: usize = 1;
+    const TITLE: &'static str = "Sonar Sweep";
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse(input)
+    }
+
+    fn part_one(input: &Self::Input) -> Result<Answer> {
+        Ok(part_one(input).into())
+    }
+
+    fn part_two(input: &Self::Input) -> Result<Option<Answer>> {
+        Ok(Some(part_two(input).into()))
+    }
 }
 
 #[cfg(test)]
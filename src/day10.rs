@@ -1,22 +1,21 @@
-use anyhow::{anyhow, Error, Result};
-use std::fs;
-use std::path::Path;
+use crate::error::{PuzzleError, Result};
+use crate::{parse, Answer, Puzzle};
 
 fn is_open_delimiter(delimiter: &char) -> bool {
     ['(', '[', '{', '<'].contains(delimiter)
 }
 
-fn pair_delimiter(delimiter: char) -> Result<(char, char), Error> {
+fn pair_delimiter(delimiter: char) -> Result<(char, char)> {
     match delimiter {
         '(' => Ok(('(', ')')),
         '[' => Ok(('[', ']')),
         '{' => Ok(('{', '}')),
         '<' => Ok(('<', '>')),
-        _ => Err(anyhow!("Incorrect input for delimiter")),
+        _ => Err(PuzzleError::invalid("Incorrect input for delimiter")),
     }
 }
 
-fn find_faulty_delimiter(delimiters: &str) -> Result<char, Error> {
+fn find_faulty_delimiter(delimiters: &str) -> Result<char> {
     let mut checks: Vec<(char, char)> = Vec::new();
 
     for c in delimiters.chars() {
@@ -35,14 +34,14 @@ fn find_faulty_delimiter(delimiters: &str) -> Result<char, Error> {
                     return Ok(c);
                 }
             }
-            None => return Err(anyhow!("Incorrect format")),
+            None => return Err(PuzzleError::invalid("Incorrect format")),
         }
     }
 
-    Err(anyhow!("Nothing found error"))
+    Err(PuzzleError::no_solution("Nothing found error"))
 }
 
-fn missing_delimiter(delimiters: &str) -> Result<Vec<char>, Error> {
+fn missing_delimiter(delimiters: &str) -> Result<Vec<char>> {
     let mut checks: Vec<(char, char)> = Vec::new();
 
     for c in delimiters.chars() {
@@ -58,10 +57,10 @@ fn missing_delimiter(delimiters: &str) -> Result<Vec<char>, Error> {
                 if check.1 == c {
                     continue;
                 } else {
-                    return Err(anyhow!("Is faulty chunk"));
+                    return Err(PuzzleError::invalid("Is faulty chunk"));
                 }
             }
-            None => return Err(anyhow!("Incorrect format")),
+            None => return Err(PuzzleError::invalid("Incorrect format")),
         }
     }
 
@@ -88,10 +87,20 @@ fn missing(delimiter: &char) -> usize {
     }
 }
 
-fn part_one(data: &str) -> usize {
-    let v: usize = data
-        .trim()
-        .lines()
+pub fn parse(data: &str) -> Result<Vec<String>> {
+    parse::lines(data)
+        .map(
+            |(n, line)| match line.chars().position(|c| !"()[]{}<>".contains(c)) {
+                Some(j) => Err(PuzzleError::parse(n, j + 1, "Expected a delimiter")),
+                None => Ok(String::from(line)),
+            },
+        )
+        .collect()
+}
+
+pub fn part_one(lines: &[String]) -> usize {
+    let v: usize = lines
+        .iter()
         .map(|ds| find_faulty_delimiter(ds))
         .map(|fd| match fd {
             Ok(d) => incorrectness(&d),
@@ -101,32 +110,51 @@ fn part_one(data: &str) -> usize {
     v
 }
 
-fn part_two(data: &str) -> usize {
-    let mut v = data
-        .trim()
-        .lines()
+pub fn part_two(lines: &[String]) -> Result<usize> {
+    let mut v = lines
+        .iter()
         .map(|ds| missing_delimiter(ds))
         .flat_map(|fd| match fd {
-            Ok(d) => vec![d.iter().rev().map(missing).fold(0, |acc, v| {
-                let new_acc = acc * 5 + v;
-                new_acc
-            })],
+            Ok(d) => vec![d.iter().rev().map(missing).fold(0, |acc, v| acc * 5 + v)],
             Err(_) => vec![],
         })
         .collect::<Vec<usize>>();
 
     v.sort();
 
-    v[v.len() / 2]
+    v.get(v.len() / 2)
+        .copied()
+        .ok_or_else(|| PuzzleError::no_solution("There are no incomplete lines"))
 }
 
-pub fn main(path: &Path) -> Result<(usize, Option<usize>)> {
-    let content = fs::read_to_string(path)?;
+pub struct Day10;
+
+impl Puzzle for Day10 {
+    type Input = Vec<String>;
+
+    const DAY: usize = 10;
+    const TITLE: &'static str = "Syntax Scoring";
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse(input)
+    }
+
+    fn part_one(input: &Self::Input) -> Result<Answer> {
+        Ok(part_one(input).into())
+    }
 
-    Ok((part_one(&content), Some(part_two(&content))))
+    fn part_two(input: &Self::Input) -> Result<Option<Answer>> {
+        Ok(Some(part_two(input)?.into()))
+    }
 }
 
 #[cfg(test)]
+#[allow(
+    non_snake_case,
+    clippy::explicit_auto_deref,
+    clippy::let_and_return,
+    clippy::useless_vec
+)]
 mod tests {
     use super::*;
     #[test]
@@ -191,4 +219,14 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_parse_error() -> Result<()> {
+        let e = parse("[({(<(())[]>[[{[]{<()<>>\n[(()[<>]a)]").unwrap_err();
+        assert_eq!(e.to_string(), "line 2, column 9: Expected a delimiter");
+
+        let e = part_two(&parse("{([(<{}[<>[]}>{[]{[(<()>")?).unwrap_err();
+        assert!(matches!(e, PuzzleError::NoSolution(_)));
+        Ok(())
+    }
 }
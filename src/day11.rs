@@ -1,16 +1,10 @@
-use anyhow::Result;
-use std::collections::{HashMap, HashSet, VecDeque};
-use std::fs;
-use std::path::Path;
-
-#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
-struct Coordinate {
-    x: isize,
-    y: isize,
-}
+use crate::error::{PuzzleError, Result};
+use crate::grid::Grid;
+use crate::{Answer, Puzzle};
+use std::collections::HashSet;
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
-struct Octopus {
+pub struct Octopus {
     energy: u32,
 }
 
@@ -23,78 +17,49 @@ impl Octopus {
     }
 }
 
-fn neighbors(Coordinate { x, y }: &Coordinate) -> Vec<Coordinate> {
-    [
-        Coordinate { x: *x - 1, y: *y },
-        Coordinate {
-            x: *x - 1,
-            y: *y - 1,
-        },
-        Coordinate {
-            x: *x - 1,
-            y: *y + 1,
-        },
-        Coordinate { x: *x + 1, y: *y },
-        Coordinate {
-            x: *x + 1,
-            y: *y - 1,
-        },
-        Coordinate {
-            x: *x + 1,
-            y: *y + 1,
-        },
-        Coordinate { x: *x, y: *y - 1 },
-        Coordinate { x: *x, y: *y + 1 },
-    ]
-    .into_iter()
-    .filter(|coord| coord.x > -1 && coord.y > -1 && coord.x < 10 && coord.y < 10)
-    .collect::<Vec<Coordinate>>()
-}
+/// The octopuses always form a square of this size
+const SIZE: usize = 10;
 
-fn octopuses(data: &str) -> Result<HashMap<Coordinate, Octopus>> {
-    let mut octo = HashMap::new();
-
-    for (y, line) in data.trim().lines().enumerate() {
-        for (x, c) in line.chars().enumerate() {
-            let energy = c.to_string().parse()?;
-            octo.insert(
-                Coordinate {
-                    x: x.try_into()?,
-                    y: y.try_into()?,
-                },
-                Octopus { energy },
-            );
-        }
+pub fn parse(data: &str) -> Result<Grid<Octopus>> {
+    let octo = Grid::parse(data, "an energy level", |c| {
+        c.to_digit(10).map(|energy| Octopus { energy })
+    })?;
+
+    if octo.width() != SIZE || octo.height() != SIZE {
+        return Err(PuzzleError::invalid(format!(
+            "Expected {} rows of {} octopuses",
+            SIZE, SIZE
+        )));
     }
 
     Ok(octo)
 }
 
-fn step(state: &HashMap<Coordinate, Octopus>) -> (HashMap<Coordinate, Octopus>, usize) {
+fn step(state: &Grid<Octopus>) -> (Grid<Octopus>, usize) {
     let mut flashed = HashSet::new();
-    let mut queue: VecDeque<Coordinate> = VecDeque::new();
+    let mut queue = Vec::new();
     let mut new_state = state.clone();
 
-    for (coord, octo) in new_state.iter_mut() {
+    for point in state.points() {
+        let octo = &mut new_state[point];
         octo.charge();
         if octo.energy == 0 {
-            flashed.insert(*coord);
-            queue.push_back(*coord);
+            flashed.insert(point);
+            queue.push(point);
         }
     }
 
-    while queue.is_empty() == false {
-        let coord = queue.pop_back().unwrap();
-        for n_coord in neighbors(&coord) {
-            if flashed.contains(&n_coord) {
+    while let Some(point) = queue.pop() {
+        for n_point in state.neighbours8(point) {
+            if flashed.contains(&n_point) {
                 continue;
             }
 
-            let octo = new_state.entry(n_coord).or_insert(Octopus { energy: 0 });
+            let octo = &mut new_state[n_point];
             octo.charge();
             if octo.energy == 0 {
-                flashed.insert(n_coord);
-                queue.push_back(n_coord);
+                flashed.insert(n_point);
+                queue.push(n_point);
             }
         }
     }
@@ -102,33 +67,49 @@ fn step(state: &HashMap<Coordinate, Octopus>) -> (HashMap<Coordinate, Octopus>,
     (new_state, flashed.len())
 }
 
-fn part_one(data: &str) -> Result<usize> {
-    let (mut os, mut v) = step(&octopuses(data)?);
+pub fn part_one(octopuses: &Grid<Octopus>) -> usize {
+    let (mut os, mut v) = step(octopuses);
     for _ in 1..100 {
         let (os2, v2) = step(&os);
         os = os2;
         v += v2;
     }
 
-    Ok(v)
+    v
 }
 
-fn part_two(data: &str) -> Result<usize> {
-    let (mut os, mut v) = step(&octopuses(data)?);
+pub fn part_two(octopuses: &Grid<Octopus>) -> usize {
+    let (mut os, mut v) = step(octopuses);
     let mut steps = 1;
-    while v != os.len() {
+    while v != os.width() * os.height() {
         steps += 1;
         let (os2, v2) = step(&os);
         os = os2;
         v = v2;
     }
 
-    Ok(steps)
+    steps
 }
 
-pub fn main(path: &Path) -> Result<(usize, Option<usize>)> {
-    let content = fs::read_to_string(path)?;
-    Ok((part_one(&content)?, Some(part_two(&content)?)))
+pub struct Day11;
+
+impl Puzzle for Day11 {
+    type Input = Grid<Octopus>;
+
+    const DAY: usize = 11;
+    const TITLE: &'static str = "Dumbo Octopus";
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse(input)
+    }
+
+    fn part_one(input: &Self::Input) -> Result<Answer> {
+        Ok(part_one(input).into())
+    }
+
+    fn part_two(input: &Self::Input) -> Result<Option<Answer>> {
+        Ok(Some(part_two(input).into()))
+    }
 }
 
 #[cfg(test)]
@@ -150,7 +131,7 @@ mod tests {
 
     #[test]
     fn test_part_one() -> Result<()> {
-        let (mut os, mut v) = step(&octopuses(DATA)?);
+        let (mut os, mut v) = step(&parse(DATA)?);
         for _ in 1..100 {
             let (os2, v2) = step(&os);
             os = os2;
@@ -159,4 +140,17 @@ mod tests {
         assert_eq!(v, 1656);
         Ok(())
     }
+
+    #[test]
+    fn test_parse_error() -> Result<()> {
+        let e = parse(&DATA.replacen("2745", "27x5", 1)).unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            "line 3, column 3: 'x' is not an energy level"
+        );
+
+        let e = parse("5483143223\n2745854711").unwrap_err();
+        assert!(matches!(e, PuzzleError::InvalidInput(_)));
+        Ok(())
+    }
 }
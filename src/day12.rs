@@ -1,80 +1,77 @@
-use anyhow::{anyhow, Error, Result};
+use crate::error::{PuzzleError, Result};
+use crate::{parse, Answer, Puzzle};
 use std::collections::{HashMap, HashSet};
-use std::fs;
-use std::path::Path;
 
 #[derive(Debug, Hash, Clone, PartialEq, Eq)]
-enum NodeType {
-    ONCE,
-    TWICE,
-    START,
-    END,
-    MANY,
+pub enum NodeType {
+    Small,
+    Start,
+    End,
+    Many,
 }
 
 impl NodeType {
-    fn from_str(str: &str, twice: bool) -> Result<Self, Error> {
+    fn from_str(str: &str) -> Result<Self> {
         if str.to_uppercase() == str {
-            return Ok(NodeType::MANY);
+            return Ok(NodeType::Many);
         }
         if str == "start" {
-            return Ok(NodeType::START);
+            return Ok(NodeType::Start);
         }
         if str == "end" {
-            return Ok(NodeType::END);
+            return Ok(NodeType::End);
         }
         if str.to_lowercase() == str {
-            return Ok(if twice {
-                NodeType::TWICE
-            } else {
-                NodeType::ONCE
-            });
+            return Ok(NodeType::Small);
         }
-        Err(anyhow!("Could not handle nodetype"))
+        Err(PuzzleError::invalid("Could not handle nodetype"))
     }
 }
 
 #[derive(Debug, Clone, Eq, PartialEq)]
-struct Node<'a> {
-    id: &'a str,
-    links: HashSet<&'a str>,
+pub struct Node {
+    id: String,
+    links: HashSet<String>,
     node_type: NodeType,
 }
 
-impl<'a> Node<'a> {
-    fn add_link(&mut self, node: &'a str) {
-        self.links.insert(node);
+impl Node {
+    fn add_link(&mut self, node: &str) {
+        self.links.insert(node.to_string());
     }
 }
 
-fn get_map<'a>(data: &'a str, twice: bool) -> Result<HashMap<&'a str, Node<'a>>> {
-    let mut map: HashMap<&'a str, Node<'a>> = HashMap::new();
-    let links = data
-        .trim()
-        .lines()
-        .map(|line| {
-            line.split_once("-")
-                .map(Ok)
-                .unwrap_or(Err(anyhow!("BAD FORMAT")))
+pub fn parse(data: &str) -> Result<HashMap<String, Node>> {
+    let mut map: HashMap<String, Node> = HashMap::new();
+    let links = parse::lines(data)
+        .map(|(n, line)| {
+            let (a, b) = line
+                .split_once('-')
+                .ok_or_else(|| PuzzleError::at(n - 1, line, line, "Expected a link like a-b"))?;
+
+            for cave in [a, b] {
+                NodeType::from_str(cave).map_err(|_| {
+                    PuzzleError::at(n - 1, line, cave, format!("{:?} is not a cave", cave))
+                })?;
+            }
+            Ok((a, b))
         })
         .collect::<Result<Vec<(&str, &str)>>>()?;
 
-    for pair in links.iter() {
-        let (a, b): (&'a str, &'a str) = *pair;
-        let node_a = map.entry(a).or_insert(Node {
-            id: a,
+    for (a, b) in links.iter() {
+        let node_a = map.entry(a.to_string()).or_insert(Node {
+            id: a.to_string(),
             links: HashSet::new(),
-            node_type: NodeType::from_str(a, twice)?,
+            node_type: NodeType::from_str(a)?,
         });
         node_a.add_link(b);
     }
 
-    for pair in links.iter() {
-        let (a, b): (&'a str, &'a str) = *pair;
-        let node_b = map.entry(b).or_insert(Node {
-            id: b,
+    for (a, b) in links.iter() {
+        let node_b = map.entry(b.to_string()).or_insert(Node {
+            id: b.to_string(),
             links: HashSet::new(),
-            node_type: NodeType::from_str(b, twice)?,
+            node_type: NodeType::from_str(b)?,
         });
         node_b.add_link(a);
     }
@@ -83,26 +80,25 @@ fn get_map<'a>(data: &'a str, twice: bool) -> Result<HashMap<&'a str, Node<'a>>>
 }
 
 fn walk<'a>(
-    map: &HashMap<&str, Node<'a>>,
+    map: &'a HashMap<String, Node>,
     mut done_visit: HashSet<&'a str>,
     mut done_visit_twice: HashSet<&'a str>,
-    node: &Node<'a>,
+    node: &'a Node,
+    twice: bool,
 ) -> Result<usize> {
-    if node.node_type == NodeType::END {
+    if node.node_type == NodeType::End {
         return Ok(1);
     }
 
     match node.node_type {
-        NodeType::MANY => (),
-        NodeType::TWICE => {
-            if done_visit.contains(node.id) && done_visit_twice.len() == 0 {
-                done_visit_twice.insert(node.id);
-            } else {
-                done_visit.insert(node.id);
-            }
+        NodeType::Many => (),
+        NodeType::Small
+            if twice && done_visit.contains(node.id.as_str()) && done_visit_twice.is_empty() =>
+        {
+            done_visit_twice.insert(&node.id);
         }
         _ => {
-            done_visit.insert(node.id);
+            done_visit.insert(&node.id);
         }
     }
 
@@ -110,52 +106,69 @@ fn walk<'a>(
     for n in node.links.iter() {
         let next_node = map
             .get(n)
-            .map(Ok)
-            .unwrap_or(Err(anyhow!("Did not find next node")))?;
+            .ok_or_else(|| PuzzleError::invalid(format!("Did not find cave {}", n)))?;
 
         let skip = match next_node.node_type {
-            NodeType::TWICE => {
-                if done_visit_twice.len() == 0 {
+            NodeType::Small if twice => {
+                if done_visit_twice.is_empty() {
                     false
                 } else {
-                    done_visit.contains(n)
+                    done_visit.contains(n.as_str())
                 }
             }
-            _ => done_visit.contains(n),
+            _ => done_visit.contains(n.as_str()),
         };
 
         if skip {
             continue;
         } else {
-            visits += walk(map, done_visit.clone(), done_visit_twice.clone(), next_node)?;
+            visits += walk(
+                map,
+                done_visit.clone(),
+                done_visit_twice.clone(),
+                next_node,
+                twice,
+            )?;
         }
     }
 
     Ok(visits)
 }
 
-fn part_one(data: &str) -> Result<usize> {
-    let map = get_map(data, false)?;
-    let start_node = map
-        .get("start")
-        .map(Ok)
-        .unwrap_or(Err(anyhow!("Did not find next node")))?;
-    walk(&map, HashSet::new(), HashSet::new(), &start_node)
+fn start(map: &HashMap<String, Node>) -> Result<&Node> {
+    map.get("start")
+        .ok_or_else(|| PuzzleError::no_solution("There is no start cave"))
+}
+
+pub fn part_one(map: &HashMap<String, Node>) -> Result<usize> {
+    let start_node = start(map)?;
+    walk(map, HashSet::new(), HashSet::new(), start_node, false)
 }
 
-fn part_two(data: &str) -> Result<usize> {
-    let map = get_map(data, true)?;
-    let start_node = map
-        .get("start")
-        .map(Ok)
-        .unwrap_or(Err(anyhow!("Did not find next node")))?;
-    walk(&map, HashSet::new(), HashSet::new(), &start_node)
+pub fn part_two(map: &HashMap<String, Node>) -> Result<usize> {
+    let start_node = start(map)?;
+    walk(map, HashSet::new(), HashSet::new(), start_node, true)
 }
 
-pub fn main(path: &Path) -> Result<(usize, Option<usize>)> {
-    let content = fs::read_to_string(path)?;
+pub struct Day12;
+
+impl Puzzle for Day12 {
+    type Input = HashMap<String, Node>;
+
+    const DAY: usize = 12;
+    const TITLE: &'static str = "Passage Pathing";
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse(input)
+    }
+
+    fn part_one(input: &Self::Input) -> Result<Answer> {
+        Ok(part_one(input)?.into())
+    }
 
-    Ok((part_one(&content)?, Some(part_two(&content)?)))
+    fn part_two(input: &Self::Input) -> Result<Option<Answer>> {
+        Ok(Some(part_two(input)?.into()))
+    }
 }
 
 #[cfg(test)]
@@ -173,15 +186,22 @@ mod tests {
 
     #[test]
     fn test_walk() -> Result<()> {
-        let map = get_map(SMALL_DATA, true)?;
+        let map = parse(SMALL_DATA)?;
 
-        let start_node = map
-            .get("start")
-            .map(Ok)
-            .unwrap_or(Err(anyhow!("Did not find next node")))?;
+        let start_node = start(&map)?;
 
-        let x = walk(&map, HashSet::new(), HashSet::new(), start_node)?;
+        let x = walk(&map, HashSet::new(), HashSet::new(), start_node, true)?;
         assert_eq!(x, 36);
         Ok(())
     }
+
+    #[test]
+    fn test_parse_error() -> Result<()> {
+        let e = parse("start-A\nA-cD").unwrap_err();
+        assert_eq!(e.to_string(), "line 2, column 3: \"cD\" is not a cave");
+
+        let e = part_one(&parse("A-b\nb-end")?).unwrap_err();
+        assert!(matches!(e, PuzzleError::NoSolution(_)));
+        Ok(())
+    }
 }
@@ -1,87 +1,72 @@
-use anyhow::{anyhow, Error, Result};
+use crate::error::{PuzzleError, Result};
+use crate::parse;
+use crate::point::{Point2, Vec2};
+use crate::render::{self, Color, Image};
+use crate::{Answer, Puzzle};
 use std::collections::HashSet;
-use std::fs;
-use std::path::Path;
-use std::str::FromStr;
-
-#[derive(Debug, Hash, PartialEq, Copy, Eq, Clone)]
-struct Coord {
-    x: usize,
-    y: usize,
-}
-
-impl FromStr for Coord {
-    type Err = Error;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (x, y) = s
-            .split_once(",")
-            .map(Ok)
-            .unwrap_or(Err(anyhow!("Bad format")))?;
-
-        Ok(Coord {
-            x: x.parse()?,
-            y: y.parse()?,
-        })
-    }
-}
 
+/// Split the paper along a fold line into the dots before the line and the
+/// dots beyond it, which are reflected onto the part before it
 fn split(
-    paper: HashSet<Coord>,
+    paper: HashSet<Point2>,
     (direction, line): (char, usize),
-) -> (HashSet<Coord>, HashSet<Coord>) {
+) -> Result<(HashSet<Point2>, HashSet<Point2>)> {
+    // Points away from the fold line, towards the part that is folded over
+    let normal = match direction {
+        'x' => Vec2::new(1, 0),
+        'y' => Vec2::new(0, 1),
+        _ => Vec2::default(),
+    };
+    let beyond = |dot: &Point2| (*dot - Point2::default()).dot(&normal) - line as isize;
+
     let left = paper
-        .clone()
-        .into_iter()
-        .filter(|coord| match direction {
-            'x' => coord.x < line,
-            'y' => coord.y < line,
-            _ => true,
-        })
-        .collect::<HashSet<Coord>>();
+        .iter()
+        .filter(|dot| beyond(dot) < 0)
+        .copied()
+        .collect::<HashSet<Point2>>();
 
     let right = paper
-        .clone()
-        .into_iter()
-        .filter(|coord| match direction {
-            'x' => coord.x > line,
-            'y' => coord.y > line,
-            _ => true,
-        })
-        .map(|v| match direction {
-            'x' => Coord {
-                x: 2 * line - v.x,
-                y: v.y,
-            },
-            'y' => Coord {
-                x: v.x,
-                y: 2 * line - v.y,
-            },
-            _ => v,
+        .iter()
+        .filter(|dot| beyond(dot) > 0)
+        .map(|dot| {
+            let mirrored = *dot - normal * (2 * beyond(dot));
+            if mirrored.x < 0 || mirrored.y < 0 {
+                return Err(PuzzleError::invalid(format!(
+                    "Folding along {}={} moves {},{} off the paper",
+                    direction, line, dot.x, dot.y
+                )));
+            }
+            Ok(mirrored)
         })
-        .collect::<HashSet<Coord>>();
+        .collect::<Result<HashSet<Point2>>>()?;
 
-    (left, right)
+    Ok((left, right))
 }
 
-fn fold<'a>((left, right): (&'a HashSet<Coord>, &'a HashSet<Coord>)) -> HashSet<Coord> {
-    left.union(&right)
-        .clone()
-        .map(|v| *v)
-        .collect::<HashSet<Coord>>()
+fn fold<'a>((left, right): (&'a HashSet<Point2>, &'a HashSet<Point2>)) -> HashSet<Point2> {
+    left.union(right).copied().collect::<HashSet<Point2>>()
 }
 
-fn paper(data: &str) -> Result<HashSet<Coord>> {
-    data.trim()
-        .lines()
-        .map(Coord::from_str)
-        .collect::<Result<HashSet<Coord>>>()
+pub fn parse(data: &str) -> Result<HashSet<Point2>> {
+    parse::lines(data)
+        .map(|(n, line)| {
+            let dot = parse::token::<Point2>(line, line).map_err(|e| e.shift(n, 1))?;
+            if dot.x < 0 || dot.y < 0 {
+                return Err(PuzzleError::parse(
+                    n,
+                    1,
+                    "Dots can't have negative coordinates",
+                ));
+            }
+            Ok(dot)
+        })
+        .collect::<Result<HashSet<Point2>>>()
 }
 
-fn print_paper(paper: &HashSet<Coord>) -> Option<String> {
+fn print_paper(paper: &HashSet<Point2>) -> Option<String> {
     let coords = paper
         .iter()
-        .map(|coord| (coord.x, coord.y))
+        .filter_map(|dot| <(usize, usize)>::try_from(*dot).ok())
         .collect::<Vec<(usize, usize)>>();
     let big_x = coords.iter().map(|(x, _)| x).max()?;
     let big_y = coords.iter().map(|(_, y)| y).max()?;
@@ -90,34 +75,34 @@ fn print_paper(paper: &HashSet<Coord>) -> Option<String> {
 
     for y in 0..=*big_y {
         for x in 0..=*big_x {
-            let v = paper.get(&Coord { x, y });
+            let v = paper.get(&Point2::new(x as isize, y as isize));
             match v {
                 Some(_) => {
                     // print!("#");
-                    code.push_str("#");
+                    code.push('#');
                 }
                 None => {
                     // print!("_");
-                    code.push_str("_");
+                    code.push('_');
                 }
             }
         }
-        code.push_str("\n");
+        code.push('\n');
         // println!("");
     }
 
     Some(code)
 }
 
-fn part_one(data: &str) -> Result<usize> {
-    let paper = paper(data)?;
-    let (left, right) = split(paper, ('x', 655));
+pub fn part_one(paper: &HashSet<Point2>) -> Result<usize> {
+    let (left, right) = split(paper.clone(), ('x', 655))?;
     let v = fold((&left, &right));
     Ok(v.len())
 }
 
-fn part_two(data: &str) -> Option<String> {
-    let mut paper = paper(data).unwrap();
+/// The paper after every fold
+fn fold_all(paper: &HashSet<Point2>) -> Result<HashSet<Point2>> {
+    let mut paper = paper.clone();
     let inputs = [
         ('x', 655),
         ('y', 447),
@@ -134,17 +119,46 @@ fn part_two(data: &str) -> Option<String> {
     ];
 
     for input in inputs {
-        let (left, right) = split(paper, input);
+        let (left, right) = split(paper, input)?;
         paper = fold((&left, &right));
     }
 
-    print_paper(&paper)
+    Ok(paper)
+}
+
+pub fn part_two(paper: &HashSet<Point2>) -> Result<String> {
+    print_paper(&fold_all(paper)?).ok_or_else(|| PuzzleError::no_solution("The paper has no dots"))
+}
+
+/// The folded paper, with the dots in black
+pub fn render(paper: &HashSet<Point2>) -> Result<Image> {
+    render::points(&fold_all(paper)?, Color::BLACK, Color::WHITE)
+        .ok_or_else(|| PuzzleError::no_solution("The paper has no dots"))
 }
 
-pub fn main(path: &Path) -> Result<(usize, Option<String>)> {
-    let content = fs::read_to_string(path)?;
+pub struct Day13;
+
+impl Puzzle for Day13 {
+    type Input = HashSet<Point2>;
+
+    const DAY: usize = 13;
+    const TITLE: &'static str = "Transparent Origami";
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse(input)
+    }
+
+    fn part_one(input: &Self::Input) -> Result<Answer> {
+        Ok(part_one(input)?.into())
+    }
+
+    fn part_two(input: &Self::Input) -> Result<Option<Answer>> {
+        Ok(Some(Answer::grid(&part_two(input)?)))
+    }
 
-    Ok((part_one(&content)?, part_two(&content)))
+    fn render(input: &Self::Input) -> Result<Option<Image>> {
+        Ok(Some(render(input)?))
+    }
 }
 
 #[cfg(test)]
@@ -155,4 +169,17 @@ mod tests {
     fn test_something() -> Result<()> {
         Ok(())
     }
+
+    #[test]
+    fn test_parse_error() -> Result<()> {
+        let e = parse("6,10\n0,1a4").unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            "line 2, column 3: invalid digit found in string"
+        );
+
+        let e = part_one(&parse("1400,10")?).unwrap_err();
+        assert!(matches!(e, PuzzleError::InvalidInput(_)));
+        Ok(())
+    }
 }
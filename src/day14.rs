@@ -1,22 +1,57 @@
-use anyhow::Result;
+use crate::error::{PuzzleError, Result};
+use crate::parse;
+use crate::{Answer, Puzzle};
 use std::collections::HashMap;
-use std::fs;
-use std::path::Path;
 
-fn apply_rules(pair: &str, rules: &Vec<(&str, char)>) -> Option<(String, String)> {
-    let (_, c) = rules.iter().find(|(rule, _)| *rule == pair)?;
-    let mut left: String = String::from(&pair[0..1]);
+fn apply_rules(pair: &str, rules: &[(String, char)]) -> Result<(String, String)> {
+    let (_, c) = rules
+        .iter()
+        .find(|(rule, _)| *rule == pair)
+        .ok_or_else(|| PuzzleError::no_solution(format!("No rule for the pair {}", pair)))?;
+    let mut chars = pair.chars();
+    let mut left: String = chars.next().into_iter().collect();
     left.push(*c);
     let mut right: String = c.to_string();
-    right.push_str(&pair[1..2]);
+    right.extend(chars);
 
-    Some((left, right))
+    Ok((left, right))
 }
 
-fn setup(data: &str) -> HashMap<String, usize> {
-    data.lines()
-        .take(1)
-        .flat_map(|x| x.chars())
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Polymer {
+    pairs: HashMap<String, usize>,
+    last: char,
+    rules: Vec<(String, char)>,
+}
+
+pub fn parse(data: &str) -> Result<Polymer> {
+    let sections = parse::sections(data);
+    let (line, template, last) = sections
+        .first()
+        .and_then(|(line, template)| Some((*line, *template, template.chars().last()?)))
+        .ok_or_else(|| PuzzleError::invalid("The polymer template is empty"))?;
+
+    let rules = match sections.get(1) {
+        Some((line, section)) => rules(section).map_err(|e| e.shift(*line, 1))?,
+        None => {
+            return Err(PuzzleError::parse(
+                line + template.lines().count(),
+                1,
+                "Expected pair insertion rules after the template",
+            ))
+        }
+    };
+
+    Ok(Polymer {
+        pairs: setup(template),
+        last,
+        rules,
+    })
+}
+
+fn setup(template: &str) -> HashMap<String, usize> {
+    template
+        .chars()
         .collect::<Vec<char>>()
         .windows(2)
         .fold(HashMap::new(), |mut acc, p| {
@@ -25,55 +60,96 @@ fn setup(data: &str) -> HashMap<String, usize> {
         })
 }
 
-fn rules(data: &str) -> Vec<(&str, char)> {
-    data.trim()
-        .lines()
-        .skip(2)
-        .map(|line| line.split_once(" -> ").unwrap())
-        .map(|(pair, c)| (pair, c.chars().last().unwrap()))
-        .collect::<Vec<(&str, char)>>()
+fn rules(section: &str) -> Result<Vec<(String, char)>> {
+    parse::lines(section)
+        .map(|(n, line)| {
+            let (pair, c) = parse::arrow(line).map_err(|e| e.shift(n, 1))?;
+
+            if pair.chars().count() != 2 {
+                return Err(PuzzleError::at(
+                    n - 1,
+                    line,
+                    pair,
+                    "Expected a pair of elements",
+                ));
+            }
+            let mut c_chars = c.chars();
+            match (c_chars.next(), c_chars.next()) {
+                (Some(c), None) => Ok((pair.to_string(), c)),
+                _ => Err(PuzzleError::at(n - 1, line, c, "Expected a single element")),
+            }
+        })
+        .collect::<Result<Vec<(String, char)>>>()
 }
 
-fn step(pairs: &HashMap<String, usize>, rules: &Vec<(&str, char)>) -> HashMap<String, usize> {
+fn step(
+    pairs: &HashMap<String, usize>,
+    rules: &[(String, char)],
+) -> Result<HashMap<String, usize>> {
     let mut new_map: HashMap<String, usize> = HashMap::new();
 
     for (k, v) in pairs.iter() {
-        let (left, right) = apply_rules(k, &rules).unwrap();
+        let (left, right) = apply_rules(k, rules)?;
         *new_map.entry(left).or_insert(0) += *v;
         *new_map.entry(right).or_insert(0) += *v;
     }
-    new_map
+    Ok(new_map)
 }
 
-fn iter(data: &str, n: usize) -> usize {
-    let mut setup = setup(data);
-    let rules = rules(data);
+fn iter(polymer: &Polymer, n: usize) -> Result<usize> {
+    let mut setup = polymer.pairs.clone();
 
     for _ in 0..n {
-        setup = step(&setup, &rules);
+        setup = step(&setup, &polymer.rules)?;
     }
 
     let mut counts_map =
         setup
             .iter()
             .fold(HashMap::new(), |mut acc: HashMap<char, usize>, (s, v)| {
-                let first = s.chars().nth(0).unwrap();
-                *acc.entry(first).or_default() += v;
+                if let Some(first) = s.chars().next() {
+                    *acc.entry(first).or_default() += v;
+                }
                 acc
             });
 
-    *counts_map.entry('F').or_default() += 1;
+    // Only the first element of each pair is counted, which misses the last one
+    *counts_map.entry(polymer.last).or_default() += 1;
 
-    let max = counts_map.values().max().unwrap();
-    let min = counts_map.values().min().unwrap();
+    // The last element was just counted, so there is always a max and min
+    let max = counts_map.values().max().copied().unwrap_or_default();
+    let min = counts_map.values().min().copied().unwrap_or_default();
 
-    max - min
+    Ok(max - min)
 }
 
-pub fn main(path: &Path) -> Result<(usize, Option<usize>)> {
-    let content = fs::read_to_string(path)?;
+pub fn part_one(polymer: &Polymer) -> Result<usize> {
+    iter(polymer, 10)
+}
+
+pub fn part_two(polymer: &Polymer) -> Result<usize> {
+    iter(polymer, 40)
+}
+
+pub struct Day14;
+
+impl Puzzle for Day14 {
+    type Input = Polymer;
 
-    Ok((iter(&content, 10), Some(iter(&content, 40))))
+    const DAY: usize = 14;
+    const TITLE: &'static str = "Extended Polymerization";
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse(input)
+    }
+
+    fn part_one(input: &Self::Input) -> Result<Answer> {
+        Ok(part_one(input)?.into())
+    }
+
+    fn part_two(input: &Self::Input) -> Result<Option<Answer>> {
+        Ok(Some(part_two(input)?.into()))
+    }
 }
 
 #[cfg(test)]
@@ -84,4 +160,23 @@ mod tests {
     fn test_something() -> Result<()> {
         Ok(())
     }
+
+    #[test]
+    fn test_parse_error() -> Result<()> {
+        let e = parse("NNCB\n\nCH -> B\nHHH -> N").unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            "line 4, column 1: Expected a pair of elements"
+        );
+
+        let e = parse("NNCB\n").unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            "line 2, column 1: Expected pair insertion rules after the template"
+        );
+
+        let e = part_one(&parse("NN\n\nCH -> B")?).unwrap_err();
+        assert_eq!(e.to_string(), "no solution: No rule for the pair NN");
+        Ok(())
+    }
 }
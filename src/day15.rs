@@ -1,244 +1,80 @@
-use anyhow::{anyhow, Error, Result};
-use std::collections::{HashMap, HashSet, VecDeque};
-use std::fs;
-use std::path::Path;
-use std::str::FromStr;
-
-fn subtract(a: usize, b: usize) -> Option<usize> {
-    if b > a {
-        return None;
-    }
-    {
-        Some(a - b)
-    }
+use crate::error::{PuzzleError, Result};
+use crate::grid::{Grid, Point};
+use crate::render::{self, Color, Image, Palette};
+use crate::search::{self, Path};
+use crate::{Answer, Puzzle};
+
+/// Repeat the grid `times` in both directions, increasing the risk by one
+/// for every step right or down and wrapping from 9 back to 1
+fn tiled(grid: &Grid<usize>, times: usize) -> Grid<usize> {
+    grid.tile(times, times, |risk, (x, y)| (risk + x + y - 1) % 9 + 1)
 }
 
-#[derive(Eq, Debug, Clone, Copy, PartialEq)]
-struct Point {
-    x: usize,
-    y: usize,
-    value: usize,
-    from: Option<(usize, usize)>,
+/// The path with the lowest total risk from the top left to the bottom right,
+/// not counting the risk of the top left. Every step has a risk of at least
+/// 1, so the distance left is a guess that is never too high.
+fn safest_path(grid: &Grid<usize>) -> Result<Path<Point>> {
+    let (x, y) = (grid.width() - 1, grid.height() - 1);
+
+    search::astar(
+        (0, 0),
+        |point| grid.neighbours(*point).map(|n| (n, grid[n])),
+        |point| x - point.0 + y - point.1,
+        |point| *point == (x, y),
+    )
+    .ok_or_else(|| PuzzleError::no_solution("Did not find last path"))
 }
 
-impl Point {
-    fn coord(&self) -> (usize, usize) {
-        (self.x, self.y)
-    }
+fn lowest_risk(grid: &Grid<usize>) -> Result<usize> {
+    Ok(safest_path(grid)?.cost)
 }
 
-#[derive(Eq, Debug, Clone, PartialEq)]
-struct Grid {
-    x_size: usize,
-    y_size: usize,
-    grid: HashMap<(usize, usize), Point>,
+pub fn parse(data: &str) -> Result<Grid<usize>> {
+    Grid::digits(data, "a risk level")
 }
 
-impl Grid {
-    fn neighbours(&self, (x, y): &(usize, usize)) -> Vec<Point> {
-        [
-            //up
-            subtract(*y, 1).map(|c| self.grid.get(&(*x, c))).flatten(),
-            //right
-            self.grid.get(&(x + 1, *y)),
-            //down
-            self.grid.get(&(*x, y + 1)),
-            // left
-            subtract(*x, 1).map(|c| self.grid.get(&(c, *y))).flatten(),
-        ]
-        .iter()
-        .filter(|x| x.is_some())
-        .map(|x| x.unwrap())
-        .map(|p| Point {
-            from: Some((*x, *y)),
-            ..*p
-        })
-        .collect::<Vec<Point>>()
-    }
-
-    fn from_str_big(s: &str) -> Result<Self, Error> {
-        let mut grid: HashMap<(usize, usize), Point> = HashMap::new();
-        let trimmed = s.trim().lines();
-        let x_size = trimmed
-            .clone()
-            .last()
-            .map(|x| x.len())
-            .map(Ok)
-            .unwrap_or(Err(anyhow!("Bad format")))?;
-
-        let y_size = trimmed.clone().count();
-
-        for y_plus in 0..5 {
-            for (y, line) in trimmed.clone().enumerate() {
-                for x_plus in 0..5 {
-                    for (x, c) in line.chars().enumerate() {
-                        match c.to_digit(10) {
-                            Some(d) => {
-                                let mut new_val = d as usize;
-                                for _ in 0..(x_plus + y_plus) {
-                                    new_val += 1;
-                                    if new_val > 9 {
-                                        new_val = 1;
-                                    }
-                                }
-                                let x_coord = x + x_plus * x_size;
-                                let y_coord = y + y_plus * y_size;
-                                grid.insert(
-                                    (x_coord, y_coord),
-                                    Point {
-                                        x: x_coord,
-                                        y: y_coord,
-                                        value: new_val,
-                                        from: None,
-                                    },
-                                );
-                            }
-                            None => (),
-                        }
-                    }
-                }
-            }
-        }
-
-        Ok(Grid {
-            grid,
-            x_size: x_size * 5 - 1,
-            y_size: y_size * 5 - 1,
-        })
-    }
+pub fn part_two(grid: &Grid<usize>) -> Result<usize> {
+    lowest_risk(&tiled(grid, 5))
 }
 
-impl ToString for Grid {
-    fn to_string(&self) -> String {
-        let mut s = String::new();
-
-        for y in 0..self.y_size {
-            for x in 0..self.x_size {
-                match self.grid.get(&(x, y)) {
-                    Some(v) => s.push_str(&v.value.to_string()),
-                    None => (),
-                }
-            }
-            s.push_str("\n");
-        }
-
-        s
-    }
+pub fn part_one(grid: &Grid<usize>) -> Result<usize> {
+    lowest_risk(grid)
 }
 
-impl FromStr for Grid {
-    type Err = Error;
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut grid: HashMap<(usize, usize), Point> = HashMap::new();
-        let trimmed = s.trim().lines();
-        let x_size = trimmed
-            .clone()
-            .last()
-            .map(|x| x.len())
-            .map(Ok)
-            .unwrap_or(Err(anyhow!("Bad format")))?
-            - 1;
-
-        let y_size = trimmed.clone().count() - 1;
-
-        for (y, line) in trimmed.enumerate() {
-            for (x, c) in line.chars().enumerate() {
-                match c.to_digit(10) {
-                    Some(d) => {
-                        grid.insert(
-                            (x, y),
-                            Point {
-                                x,
-                                y,
-                                value: d as usize,
-                                from: None,
-                            },
-                        );
-                    }
-                    None => (),
-                }
-            }
-        }
-
-        Ok(Grid {
-            grid,
-            x_size,
-            y_size,
-        })
-    }
+/// The risk levels with the safest path through them in white
+pub fn render(grid: &Grid<usize>) -> Result<Image> {
+    let mut image = render::colorize(grid, Palette::Risks);
+    render::overlay(
+        &mut image,
+        safest_path(grid)?.states().copied(),
+        Color::WHITE,
+    );
+    Ok(image)
 }
 
-fn dijkstra(grid: Grid, from: (usize, usize), to: (usize, usize)) -> Vec<Point> {
-    let mut visited: HashSet<(usize, usize)> = HashSet::new();
-
-    let mut priority_queue: Vec<Point> = vec![Point {
-        x: from.0,
-        y: from.1,
-        value: 0,
-        from: None,
-    }];
-
-    let mut path = Vec::new();
+pub struct Day15;
 
-    while visited.contains(&to) == false {
-        let point = priority_queue.remove(0);
+impl Puzzle for Day15 {
+    type Input = Grid<usize>;
 
-        if visited.contains(&point.coord()) {
-            continue;
-        }
-        visited.insert(point.coord());
-        path.push(point);
-        let mut ns = grid
-            .neighbours(&point.coord())
-            .iter_mut()
-            .map(|p| {
-                p.value += point.value;
-                *p
-            })
-            .collect::<Vec<Point>>();
+    const DAY: usize = 15;
+    const TITLE: &'static str = "Chiton";
 
-        priority_queue.append(&mut ns);
-
-        priority_queue.sort_by_key(|p| p.value);
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse(input)
     }
 
-    path
-}
-
-fn part_two(data: &str) -> Result<usize> {
-    let grid = Grid::from_str_big(data)?;
-    let x = grid.x_size;
-    let y = grid.y_size;
-    let path = dijkstra(grid, (0, 0), (x, y));
-
-    let cost = path
-        .last()
-        .map(|p| p.value)
-        .map(Ok)
-        .unwrap_or(Err(anyhow!("Did not find last path")))?;
-
-    Ok(cost)
-}
-
-fn part_one(data: &str) -> Result<usize> {
-    let grid = Grid::from_str(data)?;
-    let x = grid.x_size;
-    let y = grid.y_size;
-    let path = dijkstra(grid, (0, 0), (x, y));
-
-    let cost = path
-        .last()
-        .map(|p| p.value)
-        .map(Ok)
-        .unwrap_or(Err(anyhow!("Did not find last path")))?;
-
-    Ok(cost)
-}
+    fn part_one(input: &Self::Input) -> Result<Answer> {
+        Ok(part_one(input)?.into())
+    }
 
-pub fn main(path: &Path) -> Result<(usize, Option<usize>)> {
-    let content = fs::read_to_string(path)?;
+    fn part_two(input: &Self::Input) -> Result<Option<Answer>> {
+        Ok(Some(part_two(input)?.into()))
+    }
 
-    Ok((part_one(&content)?, Some(part_two(&content)?)))
+    fn render(input: &Self::Input) -> Result<Option<Image>> {
+        Ok(Some(render(input)?))
+    }
 }
 
 #[cfg(test)]
@@ -260,13 +96,31 @@ mod tests {
 
     #[test]
     fn test_part_one() -> Result<()> {
-        assert_eq!(40, part_one(DATA)?);
+        assert_eq!(40, part_one(&parse(DATA)?)?);
         Ok(())
     }
 
     #[test]
     fn test_part_two() -> Result<()> {
-        assert_eq!(315, part_two(DATA)?);
+        assert_eq!(315, part_two(&parse(DATA)?)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_render() -> Result<()> {
+        let grid = parse(DATA)?;
+        let image = render(&grid)?;
+        let path = image.iter().filter(|(_, c)| **c == Color::WHITE).count();
+
+        assert_eq!(path, safest_path(&grid)?.steps.len());
+        assert_eq!((image[(0, 0)], image[(9, 9)]), (Color::WHITE, Color::WHITE));
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_error() -> Result<()> {
+        let e = parse(&DATA.replacen("1381", "13a1", 1)).unwrap_err();
+        assert_eq!(e.to_string(), "line 3, column 3: 'a' is not a risk level");
         Ok(())
     }
 }
@@ -0,0 +1,336 @@
+use crate::error::{PuzzleError, Result};
+use crate::{Answer, Puzzle};
+use std::fmt;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Operator {
+    Sum,
+    Product,
+    Minimum,
+    Maximum,
+    GreaterThan,
+    LessThan,
+    EqualTo,
+}
+
+impl Operator {
+    fn from_type_id(type_id: usize) -> Option<Operator> {
+        match type_id {
+            0 => Some(Operator::Sum),
+            1 => Some(Operator::Product),
+            2 => Some(Operator::Minimum),
+            3 => Some(Operator::Maximum),
+            5 => Some(Operator::GreaterThan),
+            6 => Some(Operator::LessThan),
+            7 => Some(Operator::EqualTo),
+            _ => None,
+        }
+    }
+
+    fn is_comparison(&self) -> bool {
+        matches!(
+            self,
+            Operator::GreaterThan | Operator::LessThan | Operator::EqualTo
+        )
+    }
+}
+
+impl fmt::Display for Operator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Operator::Sum => "sum",
+            Operator::Product => "product",
+            Operator::Minimum => "minimum",
+            Operator::Maximum => "maximum",
+            Operator::GreaterThan => "greater than",
+            Operator::LessThan => "less than",
+            Operator::EqualTo => "equal to",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// A packet in a BITS transmission
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Packet {
+    Literal {
+        version: usize,
+        value: usize,
+    },
+    Operator {
+        version: usize,
+        operator: Operator,
+        packets: Vec<Packet>,
+    },
+}
+
+impl Packet {
+    pub fn version_sum(&self) -> usize {
+        match self {
+            Packet::Literal { version, .. } => *version,
+            Packet::Operator {
+                version, packets, ..
+            } => version + packets.iter().map(Packet::version_sum).sum::<usize>(),
+        }
+    }
+
+    pub fn value(&self) -> Result<usize> {
+        let (operator, packets) = match self {
+            Packet::Literal { value, .. } => return Ok(*value),
+            Packet::Operator {
+                operator, packets, ..
+            } => (operator, packets),
+        };
+
+        let values = packets
+            .iter()
+            .map(Packet::value)
+            .collect::<Result<Vec<usize>>>()?;
+        let overflow =
+            || PuzzleError::no_solution(format!("The {} does not fit in 64 bits", operator));
+
+        // Packets are checked to have the right number of sub-packets when read
+        match operator {
+            Operator::Sum => values
+                .iter()
+                .try_fold(0usize, |acc, v| acc.checked_add(*v))
+                .ok_or_else(overflow),
+            Operator::Product => values
+                .iter()
+                .try_fold(1usize, |acc, v| acc.checked_mul(*v))
+                .ok_or_else(overflow),
+            Operator::Minimum => Ok(values.iter().copied().min().unwrap_or_default()),
+            Operator::Maximum => Ok(values.iter().copied().max().unwrap_or_default()),
+            Operator::GreaterThan => Ok((values[0] > values[1]) as usize),
+            Operator::LessThan => Ok((values[0] < values[1]) as usize),
+            Operator::EqualTo => Ok((values[0] == values[1]) as usize),
+        }
+    }
+
+    fn fmt_indented(&self, f: &mut fmt::Formatter, depth: usize) -> fmt::Result {
+        let indent = "  ".repeat(depth);
+        match self {
+            Packet::Literal { version, value } => {
+                writeln!(f, "{}literal v{} = {}", indent, version, value)
+            }
+            Packet::Operator {
+                version,
+                operator,
+                packets,
+            } => {
+                writeln!(f, "{}{} v{}", indent, operator, version)?;
+                for packet in packets {
+                    packet.fmt_indented(f, depth + 1)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Prints the packet as a tree, with sub-packets indented below their operator
+impl fmt::Display for Packet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.fmt_indented(f, 0)
+    }
+}
+
+/// Reads a transmission bit by bit, keeping track of the position so errors
+/// can point at the hex digit they happened in
+struct Bits {
+    bits: Vec<bool>,
+    position: usize,
+}
+
+impl Bits {
+    fn error(&self, message: impl ToString) -> PuzzleError {
+        PuzzleError::parse(1, self.position / 4 + 1, message)
+    }
+
+    fn read(&mut self, n: usize) -> Result<usize> {
+        if self.position + n > self.bits.len() {
+            // Point at the last hex digit, since the packet needs more
+            return Err(PuzzleError::parse(
+                1,
+                self.bits.len() / 4,
+                "The transmission ended in the middle of a packet",
+            ));
+        }
+
+        let value = self.bits[self.position..self.position + n]
+            .iter()
+            .fold(0, |acc, bit| acc << 1 | *bit as usize);
+        self.position += n;
+        Ok(value)
+    }
+
+    fn packet(&mut self) -> Result<Packet> {
+        let start = self.position;
+        let version = self.read(3)?;
+        let type_id = self.read(3)?;
+
+        if type_id == 4 {
+            let mut value: usize = 0;
+            loop {
+                let group = self.read(5)?;
+                value = value
+                    .checked_mul(16)
+                    .ok_or_else(|| self.error("The literal does not fit in 64 bits"))?
+                    | group & 0b1111;
+                if group & 0b10000 == 0 {
+                    return Ok(Packet::Literal { version, value });
+                }
+            }
+        }
+
+        let operator = Operator::from_type_id(type_id)
+            .ok_or_else(|| self.error(format!("Unknown packet type {}", type_id)))?;
+
+        let mut packets = Vec::new();
+        if self.read(1)? == 0 {
+            let length = self.read(15)?;
+            let end = self.position + length;
+            while self.position < end {
+                packets.push(self.packet()?);
+            }
+            if self.position != end {
+                return Err(self.error("Sub-packets do not add up to the given length"));
+            }
+        } else {
+            for _ in 0..self.read(11)? {
+                packets.push(self.packet()?);
+            }
+        }
+
+        let valid = if operator.is_comparison() {
+            packets.len() == 2
+        } else {
+            !packets.is_empty()
+        };
+        if !valid {
+            return Err(PuzzleError::parse(
+                1,
+                start / 4 + 1,
+                format!(
+                    "A {} packet can't have {} sub-packets",
+                    operator,
+                    packets.len()
+                ),
+            ));
+        }
+
+        Ok(Packet::Operator {
+            version,
+            operator,
+            packets,
+        })
+    }
+}
+
+pub fn parse(input: &str) -> Result<Packet> {
+    let line = input.trim();
+    if line.is_empty() {
+        return Err(PuzzleError::invalid("The transmission is empty"));
+    }
+
+    let mut bits = Vec::with_capacity(line.len() * 4);
+    for (i, c) in line.chars().enumerate() {
+        let digit = c
+            .to_digit(16)
+            .ok_or_else(|| PuzzleError::parse(1, i + 1, format!("{:?} is not a hex digit", c)))?;
+        bits.extend((0..4).rev().map(|shift| digit >> shift & 1 == 1));
+    }
+
+    Bits { bits, position: 0 }.packet()
+}
+
+pub fn part_one(packet: &Packet) -> usize {
+    packet.version_sum()
+}
+
+pub fn part_two(packet: &Packet) -> Result<usize> {
+    packet.value()
+}
+
+pub struct Day16;
+
+impl Puzzle for Day16 {
+    type Input = Packet;
+
+    const DAY: usize = 16;
+    const TITLE: &'static str = "Packet Decoder";
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse(input)
+    }
+
+    fn part_one(input: &Self::Input) -> Result<Answer> {
+        Ok(part_one(input).into())
+    }
+
+    fn part_two(input: &Self::Input) -> Result<Option<Answer>> {
+        Ok(Some(part_two(input)?.into()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_literal() -> Result<()> {
+        assert_eq!(
+            parse("D2FE28")?,
+            Packet::Literal {
+                version: 6,
+                value: 2021
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_operator() -> Result<()> {
+        let literal = |version, value| Packet::Literal { version, value };
+
+        assert_eq!(
+            parse("38006F45291200")?,
+            Packet::Operator {
+                version: 1,
+                operator: Operator::LessThan,
+                packets: vec![literal(6, 10), literal(2, 20)],
+            }
+        );
+        assert_eq!(
+            parse("EE00D40C823060")?,
+            Packet::Operator {
+                version: 7,
+                operator: Operator::Maximum,
+                packets: vec![literal(2, 1), literal(4, 2), literal(1, 3)],
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_display() -> Result<()> {
+        assert_eq!(
+            parse("C200B40A82")?.to_string(),
+            "sum v6\n  literal v6 = 1\n  literal v2 = 2\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_error() -> Result<()> {
+        let e = parse("D2FG28").unwrap_err();
+        assert_eq!(e.to_string(), "line 1, column 4: 'G' is not a hex digit");
+
+        let e = parse("D2FE").unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            "line 1, column 4: The transmission ended in the middle of a packet"
+        );
+        Ok(())
+    }
+}
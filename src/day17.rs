@@ -0,0 +1,203 @@
+use crate::error::{PuzzleError, Result};
+use crate::{parse, Answer, Puzzle};
+use std::ops::RangeInclusive;
+
+/// The positions of a probe after every step, starting at the origin. Drag
+/// pulls the x velocity towards zero and gravity lowers the y velocity by one
+/// each step, so the iterator never ends.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Trajectory {
+    position: (isize, isize),
+    velocity: (isize, isize),
+}
+
+impl Trajectory {
+    pub fn new(velocity: (isize, isize)) -> Trajectory {
+        Trajectory {
+            position: (0, 0),
+            velocity,
+        }
+    }
+}
+
+impl Iterator for Trajectory {
+    type Item = (isize, isize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (x, y) = self.position;
+        let (vx, vy) = self.velocity;
+
+        self.position = (x + vx, y + vy);
+        self.velocity = (vx - vx.signum(), vy - 1);
+        Some(self.position)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Target {
+    x: RangeInclusive<isize>,
+    y: RangeInclusive<isize>,
+}
+
+impl Target {
+    pub fn contains(&self, (x, y): (isize, isize)) -> bool {
+        self.x.contains(&x) && self.y.contains(&y)
+    }
+
+    /// Whether a probe launched with `velocity` is ever within the target
+    /// after a step. The target is below the launch point, so once the probe
+    /// has fallen below it it can never come back.
+    pub fn hits(&self, velocity: (isize, isize)) -> bool {
+        Trajectory::new(velocity)
+            .take_while(|(_, y)| y >= self.y.start())
+            .any(|position| self.contains(position))
+    }
+
+    /// Bounds for the x velocities that can reach the target. Drag stops a
+    /// probe launched with `v` after it has moved `v * (v + 1) / 2` in x, so
+    /// slower probes never get to the near edge, and probes faster than the
+    /// far edge overshoot on the first step.
+    fn x_velocities(&self) -> RangeInclusive<isize> {
+        let reaches = |v: isize| v * (v + 1) / 2;
+        let (near, far) = (*self.x.start(), *self.x.end());
+
+        if near > 0 {
+            (0..=far).find(|v| reaches(*v) >= near).unwrap_or(far)..=far
+        } else if far < 0 {
+            near..=-(0..=-near).find(|v| reaches(*v) >= -far).unwrap_or(-near)
+        } else {
+            near..=far
+        }
+    }
+
+    /// Bounds for the y velocities that can reach the target. A probe
+    /// launched upwards with `v` comes back down through y=0 with velocity
+    /// `-v - 1`, so it overshoots the bottom of the target in one step when
+    /// `v` is larger than `-bottom - 1`. Anything launched lower than the
+    /// bottom overshoots on the first step.
+    fn y_velocities(&self) -> RangeInclusive<isize> {
+        let bottom = *self.y.start();
+        bottom..=-bottom - 1
+    }
+
+    /// Every initial velocity whose trajectory hits the target
+    pub fn velocities(&self) -> impl Iterator<Item = (isize, isize)> + '_ {
+        self.y_velocities()
+            .flat_map(move |vy| self.x_velocities().map(move |vx| (vx, vy)))
+            .filter(move |velocity| self.hits(*velocity))
+    }
+}
+
+pub fn parse(input: &str) -> Result<Target> {
+    let line = input.trim_end();
+    let ranges = line
+        .strip_prefix("target area: ")
+        .ok_or_else(|| PuzzleError::at(0, line, line, "Expected target area: "))?;
+    let (x, y) = ranges
+        .split_once(", ")
+        .ok_or_else(|| PuzzleError::at(0, line, ranges, "Expected x=..., y=..."))?;
+
+    let target = Target {
+        x: parse::range(line, x, "x")?,
+        y: parse::range(line, y, "y")?,
+    };
+
+    if *target.y.end() >= 0 {
+        return Err(PuzzleError::invalid(
+            "The target must be below the launch point",
+        ));
+    }
+    Ok(target)
+}
+
+pub fn part_one(target: &Target) -> Result<usize> {
+    target
+        .velocities()
+        .map(|(_, vy)| if vy > 0 { vy * (vy + 1) / 2 } else { 0 })
+        .max()
+        .map(|apex| apex as usize)
+        .ok_or_else(|| PuzzleError::no_solution("No velocity hits the target"))
+}
+
+pub fn part_two(target: &Target) -> usize {
+    target.velocities().count()
+}
+
+pub struct Day17;
+
+impl Puzzle for Day17 {
+    type Input = Target;
+
+    const DAY: usize = 17;
+    const TITLE: &'static str = "Trick Shot";
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse(input)
+    }
+
+    fn part_one(input: &Self::Input) -> Result<Answer> {
+        Ok(part_one(input)?.into())
+    }
+
+    fn part_two(input: &Self::Input) -> Result<Option<Answer>> {
+        Ok(Some(part_two(input).into()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DATA: &str = "target area: x=20..30, y=-10..-5\n";
+
+    #[test]
+    fn test_parse() -> Result<()> {
+        assert_eq!(
+            parse(DATA)?,
+            Target {
+                x: 20..=30,
+                y: -10..=-5
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_trajectory() -> Result<()> {
+        let positions = Trajectory::new((7, 2)).take(8).collect::<Vec<_>>();
+        assert_eq!(
+            positions,
+            vec![
+                (7, 2),
+                (13, 3),
+                (18, 3),
+                (22, 2),
+                (25, 0),
+                (27, -3),
+                (28, -7),
+                (28, -12)
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_hits() -> Result<()> {
+        let target = parse(DATA)?;
+        assert!(target.hits((7, 2)));
+        assert!(target.hits((6, 9)));
+        assert!(!target.hits((17, -4)));
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_error() -> Result<()> {
+        let e = parse("target area: x=20..30, y=-10..-a").unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            "line 1, column 31: invalid digit found in string"
+        );
+        assert!(parse("target area: x=20..30, y=5..10").is_err());
+        Ok(())
+    }
+}
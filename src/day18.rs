@@ -0,0 +1,287 @@
+use crate::error::{PuzzleError, Result};
+use crate::{parse, Answer, Puzzle};
+use std::fmt;
+use std::ops::Add;
+use std::str::FromStr;
+
+/// A snailfish number is either a regular number or a pair of snailfish
+/// numbers, written as `[left,right]`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SnailfishNumber {
+    Regular(usize),
+    Pair(Box<SnailfishNumber>, Box<SnailfishNumber>),
+}
+
+use SnailfishNumber::{Pair, Regular};
+
+impl SnailfishNumber {
+    pub fn pair(left: SnailfishNumber, right: SnailfishNumber) -> SnailfishNumber {
+        Pair(Box::new(left), Box::new(right))
+    }
+
+    pub fn magnitude(&self) -> usize {
+        match self {
+            Regular(n) => *n,
+            Pair(left, right) => 3 * left.magnitude() + 2 * right.magnitude(),
+        }
+    }
+
+    /// Explode and split until neither applies any more
+    pub fn reduce(&mut self) {
+        while self.explode(0).is_some() || self.split() {}
+    }
+
+    /// Replace the leftmost pair of regular numbers that is nested inside four
+    /// pairs with 0. Returns the left and right values of the exploded pair
+    /// that still have to be added to the regular numbers next to it.
+    fn explode(&mut self, depth: usize) -> Option<(usize, usize)> {
+        let Pair(left, right) = self else {
+            return None;
+        };
+
+        if depth >= 4 {
+            if let (Regular(a), Regular(b)) = (left.as_ref(), right.as_ref()) {
+                let carry = (*a, *b);
+                *self = Regular(0);
+                return Some(carry);
+            }
+        }
+
+        if let Some((a, b)) = left.explode(depth + 1) {
+            right.add_leftmost(b);
+            return Some((a, 0));
+        }
+        if let Some((a, b)) = right.explode(depth + 1) {
+            left.add_rightmost(a);
+            return Some((0, b));
+        }
+        None
+    }
+
+    fn add_leftmost(&mut self, value: usize) {
+        match self {
+            Regular(n) => *n += value,
+            Pair(left, _) => left.add_leftmost(value),
+        }
+    }
+
+    fn add_rightmost(&mut self, value: usize) {
+        match self {
+            Regular(n) => *n += value,
+            Pair(_, right) => right.add_rightmost(value),
+        }
+    }
+
+    /// Split the leftmost regular number that is 10 or larger into a pair
+    fn split(&mut self) -> bool {
+        match self {
+            Regular(n) if *n >= 10 => {
+                *self = SnailfishNumber::pair(Regular(*n / 2), Regular(n.div_ceil(2)));
+                true
+            }
+            Regular(_) => false,
+            Pair(left, right) => left.split() || right.split(),
+        }
+    }
+}
+
+impl Add for SnailfishNumber {
+    type Output = SnailfishNumber;
+
+    fn add(self, other: SnailfishNumber) -> SnailfishNumber {
+        let mut sum = SnailfishNumber::pair(self, other);
+        sum.reduce();
+        sum
+    }
+}
+
+impl fmt::Display for SnailfishNumber {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Regular(n) => write!(f, "{}", n),
+            Pair(left, right) => write!(f, "[{},{}]", left, right),
+        }
+    }
+}
+
+/// Read a snailfish number starting at `chars[*position]`, leaving
+/// `position` right after it
+fn read(chars: &[char], position: &mut usize) -> Result<SnailfishNumber> {
+    let expect = |position: &mut usize, expected: char| match chars.get(*position) {
+        Some(c) if *c == expected => {
+            *position += 1;
+            Ok(())
+        }
+        c => Err(unexpected(*position, c, &format!("{:?}", expected))),
+    };
+
+    match chars.get(*position) {
+        Some('[') => {
+            *position += 1;
+            let left = read(chars, position)?;
+            expect(position, ',')?;
+            let right = read(chars, position)?;
+            expect(position, ']')?;
+            Ok(SnailfishNumber::pair(left, right))
+        }
+        Some(c) if c.is_ascii_digit() => {
+            let start = *position;
+            while chars.get(*position).is_some_and(char::is_ascii_digit) {
+                *position += 1;
+            }
+            chars[start..*position]
+                .iter()
+                .collect::<String>()
+                .parse()
+                .map(Regular)
+                .map_err(|e| PuzzleError::parse(1, start + 1, e))
+        }
+        c => Err(unexpected(*position, c, "[ or a digit")),
+    }
+}
+
+fn unexpected(position: usize, found: Option<&char>, expected: &str) -> PuzzleError {
+    let message = match found {
+        Some(c) => format!("Expected {}, found {:?}", expected, c),
+        None => format!("Expected {}, found the end of the line", expected),
+    };
+    PuzzleError::parse(1, position + 1, message)
+}
+
+impl FromStr for SnailfishNumber {
+    type Err = PuzzleError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let chars = s.chars().collect::<Vec<char>>();
+        let mut position = 0;
+
+        let number = read(&chars, &mut position)?;
+        if let Some(c) = chars.get(position) {
+            return Err(PuzzleError::parse(
+                1,
+                position + 1,
+                format!("Unexpected {:?} after the number", c),
+            ));
+        }
+        Ok(number)
+    }
+}
+
+pub fn parse(input: &str) -> Result<Vec<SnailfishNumber>> {
+    parse::each_line(input)
+}
+
+pub fn part_one(numbers: &[SnailfishNumber]) -> Result<usize> {
+    numbers
+        .iter()
+        .cloned()
+        .reduce(|sum, number| sum + number)
+        .map(|sum| sum.magnitude())
+        .ok_or_else(|| PuzzleError::no_solution("There are no numbers to add"))
+}
+
+pub fn part_two(numbers: &[SnailfishNumber]) -> Result<usize> {
+    let mut largest = None;
+    for (i, a) in numbers.iter().enumerate() {
+        for (j, b) in numbers.iter().enumerate() {
+            if i != j {
+                let magnitude = (a.clone() + b.clone()).magnitude();
+                largest = largest.max(Some(magnitude));
+            }
+        }
+    }
+
+    largest.ok_or_else(|| PuzzleError::no_solution("There are fewer than two numbers to add"))
+}
+
+pub struct Day18;
+
+impl Puzzle for Day18 {
+    type Input = Vec<SnailfishNumber>;
+
+    const DAY: usize = 18;
+    const TITLE: &'static str = "Snailfish";
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse(input)
+    }
+
+    fn part_one(input: &Self::Input) -> Result<Answer> {
+        Ok(part_one(input)?.into())
+    }
+
+    fn part_two(input: &Self::Input) -> Result<Option<Answer>> {
+        Ok(Some(part_two(input)?.into()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn number(s: &str) -> SnailfishNumber {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_round_trip() -> Result<()> {
+        for s in [
+            "[1,2]",
+            "[[1,9],[8,5]]",
+            "[[[[1,3],[5,3]],[[1,3],[8,7]]],[[[4,9],[6,9]],[[8,2],[7,3]]]]",
+        ] {
+            assert_eq!(SnailfishNumber::from_str(s)?.to_string(), s);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_explode() -> Result<()> {
+        let cases = [
+            ("[[[[[9,8],1],2],3],4]", "[[[[0,9],2],3],4]"),
+            ("[7,[6,[5,[4,[3,2]]]]]", "[7,[6,[5,[7,0]]]]"),
+            ("[[6,[5,[4,[3,2]]]],1]", "[[6,[5,[7,0]]],3]"),
+            (
+                "[[3,[2,[1,[7,3]]]],[6,[5,[4,[3,2]]]]]",
+                "[[3,[2,[8,0]]],[9,[5,[4,[3,2]]]]]",
+            ),
+        ];
+
+        for (before, after) in cases {
+            let mut n = number(before);
+            assert!(n.explode(0).is_some());
+            assert_eq!(n.to_string(), after);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_add() -> Result<()> {
+        let sum = number("[[[[4,3],4],4],[7,[[8,4],9]]]") + number("[1,1]");
+        assert_eq!(sum.to_string(), "[[[[0,7],4],[[7,8],[6,0]]],[8,1]]");
+        Ok(())
+    }
+
+    #[test]
+    fn test_magnitude() -> Result<()> {
+        assert_eq!(number("[[1,2],[[3,4],5]]").magnitude(), 143);
+        assert_eq!(
+            number("[[[[8,7],[7,7]],[[8,6],[7,7]]],[[[0,7],[6,6]],[8,7]]]").magnitude(),
+            3488
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_error() -> Result<()> {
+        let e = parse("[1,2]\n[[1,2],3").unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            "line 2, column 9: Expected ']', found the end of the line"
+        );
+
+        let e = parse("[1;2]").unwrap_err();
+        assert_eq!(e.to_string(), "line 1, column 3: Expected ',', found ';'");
+        Ok(())
+    }
+}
@@ -0,0 +1,378 @@
+use crate::error::{PuzzleError, Result};
+use crate::{parse, Answer, Puzzle};
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+use std::ops::{Add, Sub};
+use std::sync::OnceLock;
+
+/// Scanners must have this many beacons in common to be aligned
+const OVERLAP: usize = 12;
+
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Point3 {
+    pub x: isize,
+    pub y: isize,
+    pub z: isize,
+}
+
+impl Point3 {
+    pub fn new(x: isize, y: isize, z: isize) -> Point3 {
+        Point3 { x, y, z }
+    }
+
+    pub fn manhattan(&self, other: &Point3) -> usize {
+        let d = *self - *other;
+        d.x.unsigned_abs() + d.y.unsigned_abs() + d.z.unsigned_abs()
+    }
+
+    /// The squared euclidean distance, which is the same however the two
+    /// points are rotated
+    pub fn distance_squared(&self, other: &Point3) -> isize {
+        let d = *self - *other;
+        d.x * d.x + d.y * d.y + d.z * d.z
+    }
+}
+
+impl Add for Point3 {
+    type Output = Point3;
+
+    fn add(self, other: Point3) -> Point3 {
+        Point3::new(self.x + other.x, self.y + other.y, self.z + other.z)
+    }
+}
+
+impl Sub for Point3 {
+    type Output = Point3;
+
+    fn sub(self, other: Point3) -> Point3 {
+        Point3::new(self.x - other.x, self.y - other.y, self.z - other.z)
+    }
+}
+
+/// One of the 24 ways a scanner can be turned, written as picking an axis
+/// of the point for every axis of the result and then flipping some signs
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Rotation {
+    axes: [usize; 3],
+    signs: [isize; 3],
+}
+
+impl Rotation {
+    pub fn all() -> Vec<Rotation> {
+        // Each permutation of the axes with the sign its parity gives
+        let permutations = [
+            ([0, 1, 2], 1),
+            ([1, 2, 0], 1),
+            ([2, 0, 1], 1),
+            ([0, 2, 1], -1),
+            ([2, 1, 0], -1),
+            ([1, 0, 2], -1),
+        ];
+
+        let mut rotations = Vec::with_capacity(24);
+        for (axes, parity) in permutations {
+            for flips in 0..8 {
+                let signs = [0, 1, 2].map(|i| if flips & 1 << i == 0 { 1 } else { -1 });
+
+                // Mirror images have a determinant of -1 and are skipped
+                if parity * signs.iter().product::<isize>() == 1 {
+                    rotations.push(Rotation { axes, signs });
+                }
+            }
+        }
+        rotations
+    }
+
+    pub fn apply(&self, point: Point3) -> Point3 {
+        let coords = [point.x, point.y, point.z];
+        let [x, y, z] = [0, 1, 2].map(|i| coords[self.axes[i]] * self.signs[i]);
+        Point3::new(x, y, z)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Scanner {
+    pub id: usize,
+    pub beacons: Vec<Point3>,
+}
+
+impl Scanner {
+    /// Sorted distances between every pair of beacons, which don't depend
+    /// on how the scanner is turned or where it is
+    fn fingerprint(&self) -> Vec<isize> {
+        let mut distances = Vec::new();
+        for (i, a) in self.beacons.iter().enumerate() {
+            for b in &self.beacons[i + 1..] {
+                distances.push(a.distance_squared(b));
+            }
+        }
+        distances.sort_unstable();
+        distances
+    }
+}
+
+/// How many distances two sorted fingerprints have in common, counting a
+/// distance that repeats as often as it appears in both
+fn shared(a: &[isize], b: &[isize]) -> usize {
+    let (mut i, mut j, mut count) = (0, 0, 0);
+    while i < a.len() && j < b.len() {
+        match a[i].cmp(&b[j]) {
+            Ordering::Less => i += 1,
+            Ordering::Greater => j += 1,
+            Ordering::Equal => {
+                count += 1;
+                i += 1;
+                j += 1;
+            }
+        }
+    }
+    count
+}
+
+/// Every scanner turned and moved so they share the coordinates of the first
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Alignment {
+    pub beacons: HashSet<Point3>,
+    pub scanners: Vec<Point3>,
+}
+
+/// Find the rotation and offset that makes at least `OVERLAP` of `beacons`
+/// line up with `known`, returning the offset and the moved beacons
+fn overlap(known: &[Point3], beacons: &[Point3]) -> Option<(Point3, Vec<Point3>)> {
+    for rotation in Rotation::all() {
+        let rotated = beacons
+            .iter()
+            .map(|beacon| rotation.apply(*beacon))
+            .collect::<Vec<_>>();
+
+        let mut offsets: HashMap<Point3, usize> = HashMap::new();
+        for k in known {
+            for r in &rotated {
+                let count = offsets.entry(*k - *r).or_insert(0);
+                *count += 1;
+
+                if *count >= OVERLAP {
+                    let offset = *k - *r;
+                    let moved = rotated.iter().map(|r| *r + offset).collect();
+                    return Some((offset, moved));
+                }
+            }
+        }
+    }
+    None
+}
+
+fn align(scanners: &[Scanner]) -> Result<Alignment> {
+    let fingerprints = scanners
+        .iter()
+        .map(Scanner::fingerprint)
+        .collect::<Vec<_>>();
+    let shared_pairs = OVERLAP * (OVERLAP - 1) / 2;
+
+    let mut placed: Vec<Option<(Point3, Vec<Point3>)>> = vec![None; scanners.len()];
+    placed[0] = Some((Point3::default(), scanners[0].beacons.clone()));
+    let mut queue = vec![0];
+
+    while let Some(a) = queue.pop() {
+        let known = placed[a]
+            .as_ref()
+            .map(|(_, b)| b.clone())
+            .unwrap_or_default();
+
+        for b in 0..scanners.len() {
+            if placed[b].is_some() || shared(&fingerprints[a], &fingerprints[b]) < shared_pairs {
+                continue;
+            }
+
+            if let Some(found) = overlap(&known, &scanners[b].beacons) {
+                placed[b] = Some(found);
+                queue.push(b);
+            }
+        }
+    }
+
+    let mut alignment = Alignment {
+        beacons: HashSet::new(),
+        scanners: Vec::new(),
+    };
+    for (scanner, placed) in scanners.iter().zip(placed) {
+        let (position, beacons) = placed.ok_or_else(|| {
+            PuzzleError::no_solution(format!(
+                "Scanner {} does not overlap with the others",
+                scanner.id
+            ))
+        })?;
+        alignment.scanners.push(position);
+        alignment.beacons.extend(beacons);
+    }
+
+    Ok(alignment)
+}
+
+pub fn parse(input: &str) -> Result<Vec<Scanner>> {
+    let mut scanners: Vec<Scanner> = Vec::new();
+
+    for (n, line) in parse::lines(input) {
+        if let Some(header) = line.strip_prefix("--- scanner ") {
+            let id = header
+                .strip_suffix(" ---")
+                .ok_or_else(|| PuzzleError::at(n - 1, line, header, "Expected --- after the id"))?;
+            let id = parse::token(line, id).map_err(|e| e.shift(n, 1))?;
+            scanners.push(Scanner {
+                id,
+                beacons: Vec::new(),
+            });
+            continue;
+        }
+
+        let scanner = scanners
+            .last_mut()
+            .ok_or_else(|| PuzzleError::at(n - 1, line, line, "Expected --- scanner 0 ---"))?;
+
+        let coords = line.split(',').collect::<Vec<_>>();
+        if coords.len() != 3 {
+            return Err(PuzzleError::at(
+                n - 1,
+                line,
+                line,
+                "Expected a beacon like 1,2,3",
+            ));
+        }
+        let coords = coords
+            .iter()
+            .map(|c| parse::token(line, c).map_err(|e| e.shift(n, 1)))
+            .collect::<Result<Vec<isize>>>()?;
+        scanner
+            .beacons
+            .push(Point3::new(coords[0], coords[1], coords[2]));
+    }
+
+    if scanners.is_empty() {
+        return Err(PuzzleError::invalid("There are no scanners"));
+    }
+    Ok(scanners)
+}
+
+/// The scanners of the input, with their alignment worked out the first time
+/// a part needs it so the other part can reuse it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Scanners {
+    pub scanners: Vec<Scanner>,
+    alignment: OnceLock<Alignment>,
+}
+
+impl Scanners {
+    pub fn new(scanners: Vec<Scanner>) -> Scanners {
+        Scanners {
+            scanners,
+            alignment: OnceLock::new(),
+        }
+    }
+
+    pub fn alignment(&self) -> Result<&Alignment> {
+        if let Some(alignment) = self.alignment.get() {
+            return Ok(alignment);
+        }
+        let alignment = align(&self.scanners)?;
+        Ok(self.alignment.get_or_init(|| alignment))
+    }
+}
+
+pub fn part_one(scanners: &Scanners) -> Result<usize> {
+    Ok(scanners.alignment()?.beacons.len())
+}
+
+pub fn part_two(scanners: &Scanners) -> Result<usize> {
+    let positions = &scanners.alignment()?.scanners;
+
+    let mut largest = 0;
+    for (i, a) in positions.iter().enumerate() {
+        for b in &positions[i + 1..] {
+            largest = largest.max(a.manhattan(b));
+        }
+    }
+    Ok(largest)
+}
+
+pub struct Day19;
+
+impl Puzzle for Day19 {
+    type Input = Scanners;
+
+    const DAY: usize = 19;
+    const TITLE: &'static str = "Beacon Scanner";
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse(input).map(Scanners::new)
+    }
+
+    fn part_one(input: &Self::Input) -> Result<Answer> {
+        Ok(part_one(input)?.into())
+    }
+
+    fn part_two(input: &Self::Input) -> Result<Option<Answer>> {
+        Ok(Some(part_two(input)?.into()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rotations() -> Result<()> {
+        let point = Point3::new(1, 2, 3);
+        let rotated = Rotation::all()
+            .iter()
+            .map(|r| r.apply(point))
+            .collect::<HashSet<_>>();
+
+        assert_eq!(rotated.len(), 24);
+        assert!(rotated.contains(&Point3::new(-2, 1, 3)));
+        assert!(!rotated.contains(&Point3::new(-1, 2, 3)));
+        Ok(())
+    }
+
+    #[test]
+    fn test_shared() -> Result<()> {
+        // Four corners of a square repeat both of their distances
+        let square = Scanner {
+            id: 0,
+            beacons: vec![
+                Point3::new(0, 0, 0),
+                Point3::new(1, 0, 0),
+                Point3::new(0, 1, 0),
+                Point3::new(1, 1, 0),
+            ],
+        };
+        let fingerprint = square.fingerprint();
+
+        assert_eq!(fingerprint, vec![1, 1, 1, 1, 2, 2]);
+        assert_eq!(shared(&fingerprint, &fingerprint), 6);
+        assert_eq!(shared(&fingerprint, &[1, 2, 2, 2, 5]), 3);
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse() -> Result<()> {
+        let scanners = parse("--- scanner 0 ---\n0,2,0\n4,1,0\n\n--- scanner 1 ---\n-1,-1,1\n")?;
+
+        assert_eq!(scanners.len(), 2);
+        assert_eq!(scanners[1].id, 1);
+        assert_eq!(scanners[0].beacons[1], Point3::new(4, 1, 0));
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_error() -> Result<()> {
+        let e = parse("--- scanner 0 ---\n0,2,0\n4,x,0").unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            "line 3, column 3: invalid digit found in string"
+        );
+
+        let e = parse("0,2,0").unwrap_err();
+        assert!(matches!(e, PuzzleError::Parse { line: 1, .. }));
+        Ok(())
+    }
+}
@@ -1,8 +1,8 @@
-use anyhow::Result;
-use std::fs;
-use std::path::Path;
+use crate::error::{PuzzleError, Result};
+use crate::{parse, Answer, Puzzle};
 
-enum Move<X> {
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Move<X> {
     Forward(X),
     Down(X),
     Up(X),
@@ -84,42 +84,61 @@ impl Sub for SubMarine1D {
     }
 }
 
-fn dive_and_get_position(input: &str, sub: &mut impl Sub) -> usize {
-    let movements = input.lines().map(|line| {
-        let (direction, _distance) = line.split_once(" ").unwrap();
-        let distance = _distance
-            .parse::<usize>()
-            .expect("Failed to parse distance");
-
-        match direction {
-            "forward" => Move::Forward(distance),
-            "down" => Move::Down(distance),
-            "up" => Move::Up(distance),
-            _ => panic!("Invalid direction"),
-        }
-    });
+pub fn parse(input: &str) -> Result<Vec<Move<usize>>> {
+    parse::lines(input)
+        .map(|(n, line)| {
+            let (direction, _distance) = line.split_once(' ').ok_or_else(|| {
+                PuzzleError::at(n - 1, line, line, "Expected direction and distance")
+            })?;
+            let distance = parse::token(line, _distance).map_err(|e| e.shift(n, 1))?;
+
+            match direction {
+                "forward" => Ok(Move::Forward(distance)),
+                "down" => Ok(Move::Down(distance)),
+                "up" => Ok(Move::Up(distance)),
+                _ => Err(PuzzleError::at(n - 1, line, direction, "Invalid direction")),
+            }
+        })
+        .collect()
+}
 
+fn dive_and_get_position(movements: &[Move<usize>], sub: &mut impl Sub) -> usize {
     for m in movements {
-        sub.dive(m);
+        sub.dive(*m);
     }
 
     sub.get_position()
 }
 
-fn part_two(input: &str) -> usize {
+pub fn part_two(movements: &[Move<usize>]) -> usize {
     let mut sub_marine = SubMarine2D::new(0, 0);
-    dive_and_get_position(input, &mut sub_marine)
+    dive_and_get_position(movements, &mut sub_marine)
 }
 
-fn part_one(input: &str) -> usize {
+pub fn part_one(movements: &[Move<usize>]) -> usize {
     let mut sub_marine = SubMarine1D::new(0, 0);
-    dive_and_get_position(input, &mut sub_marine)
+    dive_and_get_position(movements, &mut sub_marine)
 }
 
-pub fn main(path: &Path) -> Result<(usize, Option<usize>)> {
-    let input = fs::read_to_string(path).expect("Failed to read");
+pub struct Day2;
+
+impl Puzzle for Day2 {
+    type Input = Vec<Move<usize>>;
+
+    const DAY: usize = 2;
+    const TITLE: &'static str = "Dive!";
 
-    Ok((part_one(&input), Some(part_two(&input))))
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse(input)
+    }
+
+    fn part_one(input: &Self::Input) -> Result<Answer> {
+        Ok(part_one(input).into())
+    }
+
+    fn part_two(input: &Self::Input) -> Result<Option<Answer>> {
+        Ok(Some(part_two(input).into()))
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,270 @@
+use crate::error::{PuzzleError, Result};
+use crate::{parse, Answer, Puzzle};
+use std::fmt;
+
+/// The image enhancement algorithm, giving the new value of a pixel for each
+/// 9-bit number read from the 3x3 square around it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Algorithm(Vec<bool>);
+
+impl Algorithm {
+    const LEN: usize = 512;
+
+    pub fn lookup(&self, index: usize) -> bool {
+        self.0[index]
+    }
+}
+
+/// A finite image on top of an infinite background where every pixel is the
+/// same. The background can become lit when index 0 of the algorithm is `#`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Image {
+    pixels: Vec<Vec<bool>>,
+    background: bool,
+}
+
+impl Image {
+    pub fn width(&self) -> usize {
+        self.pixels.first().map_or(0, Vec::len)
+    }
+
+    pub fn height(&self) -> usize {
+        self.pixels.len()
+    }
+
+    /// The pixel at `(x, y)`, which can be outside the image
+    pub fn get(&self, x: isize, y: isize) -> bool {
+        if x < 0 || y < 0 {
+            return self.background;
+        }
+        self.pixels
+            .get(y as usize)
+            .and_then(|row| row.get(x as usize))
+            .copied()
+            .unwrap_or(self.background)
+    }
+
+    /// Apply one step of the algorithm. The image grows by one pixel on each
+    /// side, since those are the only pixels that can differ from the
+    /// background.
+    pub fn enhance(&self, algorithm: &Algorithm) -> Image {
+        let (width, height) = (self.width() as isize, self.height() as isize);
+
+        let pixels = (-1..=height)
+            .map(|y| {
+                (-1..=width)
+                    .map(|x| {
+                        let mut index = 0;
+                        for dy in -1..=1 {
+                            for dx in -1..=1 {
+                                index = index << 1 | self.get(x + dx, y + dy) as usize;
+                            }
+                        }
+                        algorithm.lookup(index)
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let background = if self.background {
+            algorithm.lookup(Algorithm::LEN - 1)
+        } else {
+            algorithm.lookup(0)
+        };
+
+        Image { pixels, background }
+    }
+
+    /// The number of lit pixels, or `None` if the background is lit
+    pub fn lit(&self) -> Option<usize> {
+        if self.background {
+            return None;
+        }
+        Some(self.pixels.iter().flatten().filter(|pixel| **pixel).count())
+    }
+}
+
+/// Prints lit pixels as `#` and dark ones as `_`, like the paper of day 13
+impl fmt::Display for Image {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for row in &self.pixels {
+            let line = row
+                .iter()
+                .map(|pixel| if *pixel { '#' } else { '_' })
+                .collect::<String>();
+            writeln!(f, "{}", line)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrenchMap {
+    pub algorithm: Algorithm,
+    pub image: Image,
+}
+
+impl TrenchMap {
+    /// The image after enhancing it `steps` times
+    pub fn enhance(&self, steps: usize) -> Image {
+        (0..steps).fold(self.image.clone(), |image, _| {
+            image.enhance(&self.algorithm)
+        })
+    }
+}
+
+fn read_pixels(n: usize, line: &str) -> Result<Vec<bool>> {
+    line.chars()
+        .enumerate()
+        .map(|(column, c)| match c {
+            '#' => Ok(true),
+            '.' => Ok(false),
+            _ => Err(PuzzleError::parse(
+                n,
+                column + 1,
+                format!("Expected '#' or '.', found {:?}", c),
+            )),
+        })
+        .collect()
+}
+
+pub fn parse(input: &str) -> Result<TrenchMap> {
+    let mut lines = parse::lines(input);
+
+    let (n, line) = lines
+        .next()
+        .ok_or_else(|| PuzzleError::invalid("There is no enhancement algorithm"))?;
+    let algorithm = read_pixels(n, line)?;
+    if algorithm.len() != Algorithm::LEN {
+        return Err(PuzzleError::at(
+            n - 1,
+            line,
+            line,
+            format!(
+                "The algorithm has {} entries instead of {}",
+                algorithm.len(),
+                Algorithm::LEN
+            ),
+        ));
+    }
+
+    let mut pixels: Vec<Vec<bool>> = Vec::new();
+    for (n, line) in lines {
+        let row = read_pixels(n, line)?;
+        if pixels.first().is_some_and(|first| first.len() != row.len()) {
+            return Err(PuzzleError::at(
+                n - 1,
+                line,
+                line,
+                "All rows of the image must be the same length",
+            ));
+        }
+        pixels.push(row);
+    }
+
+    if pixels.is_empty() {
+        return Err(PuzzleError::invalid("The image is empty"));
+    }
+
+    Ok(TrenchMap {
+        algorithm: Algorithm(algorithm),
+        image: Image {
+            pixels,
+            background: false,
+        },
+    })
+}
+
+fn lit_after(map: &TrenchMap, steps: usize) -> Result<usize> {
+    map.enhance(steps).lit().ok_or_else(|| {
+        PuzzleError::no_solution(format!(
+            "Infinitely many pixels are lit after {} steps",
+            steps
+        ))
+    })
+}
+
+pub fn part_one(map: &TrenchMap) -> Result<usize> {
+    lit_after(map, 2)
+}
+
+pub fn part_two(map: &TrenchMap) -> Result<usize> {
+    lit_after(map, 50)
+}
+
+pub struct Day20;
+
+impl Puzzle for Day20 {
+    type Input = TrenchMap;
+
+    const DAY: usize = 20;
+    const TITLE: &'static str = "Trench Map";
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse(input)
+    }
+
+    fn part_one(input: &Self::Input) -> Result<Answer> {
+        Ok(part_one(input)?.into())
+    }
+
+    fn part_two(input: &Self::Input) -> Result<Option<Answer>> {
+        Ok(Some(part_two(input)?.into()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// An algorithm that keeps the centre pixel, except that a dark square
+    /// becomes lit and a lit square becomes dark
+    fn flashing() -> String {
+        (0..Algorithm::LEN)
+            .map(|i| {
+                let lit = i == 0 || (i != Algorithm::LEN - 1 && i & 16 != 0);
+                if lit {
+                    '#'
+                } else {
+                    '.'
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_enhance() -> Result<()> {
+        let map = parse(&format!("{}\n\n#\n", flashing()))?;
+
+        let once = map.enhance(1);
+        assert_eq!(once.lit(), None);
+        assert_eq!(once.to_string(), "___\n_#_\n___\n");
+
+        // The dark ring stays dark and everything next to it that is still
+        // lit stays lit, while the background goes dark again
+        let twice = map.enhance(2);
+        assert_eq!(twice.lit(), Some(17));
+        assert_eq!(part_one(&map)?, 17);
+        Ok(())
+    }
+
+    #[test]
+    fn test_display() -> Result<()> {
+        let map = parse(&format!("{}\n\n#..\n.##\n", flashing()))?;
+        assert_eq!(map.image.to_string(), "#__\n_##\n");
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_error() -> Result<()> {
+        let e = parse(&format!("{}\n\n#.\n.x\n", flashing())).unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            "line 4, column 2: Expected '#' or '.', found 'x'"
+        );
+
+        let e = parse("#.#\n\n#\n").unwrap_err();
+        assert!(matches!(e, PuzzleError::Parse { line: 1, .. }));
+        Ok(())
+    }
+}
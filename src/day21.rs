@@ -0,0 +1,252 @@
+use crate::error::{PuzzleError, Result};
+use crate::{parse, Answer, Puzzle};
+use std::collections::HashMap;
+
+/// The number of spaces on the board of the puzzle
+const BOARD: usize = 10;
+
+/// The board size and the score needed to win, so variants of the game can
+/// be played
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Rules {
+    board: usize,
+    winning_score: usize,
+}
+
+/// Positions and scores of both players, and whose turn it is
+type State = ([usize; 2], [usize; 2], usize);
+
+impl Rules {
+    pub fn new(board: usize, winning_score: usize) -> Result<Rules> {
+        if board == 0 {
+            return Err(PuzzleError::invalid("The board needs at least one space"));
+        }
+        Ok(Rules {
+            board,
+            winning_score,
+        })
+    }
+
+    pub fn board(&self) -> usize {
+        self.board
+    }
+
+    pub fn winning_score(&self) -> usize {
+        self.winning_score
+    }
+
+    fn check_starts(&self, starts: [usize; 2]) -> Result<()> {
+        match starts
+            .iter()
+            .find(|start| !(1..=self.board).contains(*start))
+        {
+            Some(start) => Err(PuzzleError::invalid(format!(
+                "Position {} is not on a board of {} spaces",
+                start, self.board
+            ))),
+            None => Ok(()),
+        }
+    }
+
+    /// Position after moving `steps` spaces from `position`, both counting
+    /// from 1
+    fn advance(&self, position: usize, steps: usize) -> usize {
+        (position - 1 + steps) % self.board + 1
+    }
+
+    /// Play with a die that rolls 1, 2, ... up to `sides` and starts over.
+    /// Returns the losing score times the number of times the die was rolled.
+    pub fn deterministic(&self, starts: [usize; 2], sides: usize) -> Result<usize> {
+        self.check_starts(starts)?;
+        if sides == 0 {
+            return Err(PuzzleError::invalid("The die needs at least one side"));
+        }
+
+        let mut positions = starts;
+        let mut scores = [0, 0];
+        let mut rolls = 0;
+
+        for turn in [0, 1].into_iter().cycle() {
+            let steps = (0..3)
+                .map(|_| {
+                    rolls += 1;
+                    (rolls - 1) % sides + 1
+                })
+                .sum();
+
+            positions[turn] = self.advance(positions[turn], steps);
+            scores[turn] += positions[turn];
+            if scores[turn] >= self.winning_score {
+                return Ok(scores[1 - turn] * rolls);
+            }
+        }
+        unreachable!("the turns cycle forever")
+    }
+
+    /// The number of universes each player wins in, when every turn rolls a
+    /// 3-sided die three times and splits the universe for every outcome
+    pub fn dirac(&self, starts: [usize; 2]) -> Result<[usize; 2]> {
+        self.check_starts(starts)?;
+
+        // How many of the 27 ways of rolling give each sum
+        let mut sums = [0; 10];
+        for a in 1..=3 {
+            for b in 1..=3 {
+                for c in 1..=3 {
+                    sums[a + b + c] += 1;
+                }
+            }
+        }
+        let outcomes = sums
+            .iter()
+            .enumerate()
+            .filter(|(_, n)| **n > 0)
+            .map(|(sum, n)| (sum, *n))
+            .collect::<Vec<(usize, usize)>>();
+
+        Ok(self.wins((starts, [0, 0], 0), &outcomes, &mut HashMap::new()))
+    }
+
+    fn wins(
+        &self,
+        state: State,
+        outcomes: &[(usize, usize)],
+        seen: &mut HashMap<State, [usize; 2]>,
+    ) -> [usize; 2] {
+        if let Some(wins) = seen.get(&state) {
+            return *wins;
+        }
+
+        let (positions, scores, turn) = state;
+        let mut wins = [0, 0];
+        for (sum, universes) in outcomes {
+            let mut positions = positions;
+            let mut scores = scores;
+            positions[turn] = self.advance(positions[turn], *sum);
+            scores[turn] += positions[turn];
+
+            if scores[turn] >= self.winning_score {
+                wins[turn] += universes;
+            } else {
+                let next = self.wins((positions, scores, 1 - turn), outcomes, seen);
+                wins[0] += universes * next[0];
+                wins[1] += universes * next[1];
+            }
+        }
+
+        seen.insert(state, wins);
+        wins
+    }
+}
+
+pub fn parse(input: &str) -> Result<[usize; 2]> {
+    let lines = parse::lines(input).collect::<Vec<_>>();
+    if lines.len() != 2 {
+        return Err(PuzzleError::invalid(format!(
+            "Expected 2 players, found {}",
+            lines.len()
+        )));
+    }
+
+    let mut starts = [0, 0];
+    for (player, (n, line)) in lines.into_iter().enumerate() {
+        let prefix = format!("Player {} starting position: ", player + 1);
+        let position = line
+            .strip_prefix(&prefix)
+            .ok_or_else(|| PuzzleError::at(n - 1, line, line, format!("Expected {}", prefix)))?;
+        starts[player] = parse::token(line, position.trim()).map_err(|e| e.shift(n, 1))?;
+
+        if !(1..=BOARD).contains(&starts[player]) {
+            return Err(PuzzleError::at(
+                n - 1,
+                line,
+                position,
+                format!("Positions go from 1 to {}", BOARD),
+            ));
+        }
+    }
+    Ok(starts)
+}
+
+pub fn part_one(starts: &[usize; 2]) -> Result<usize> {
+    Rules::new(BOARD, 1000)?.deterministic(*starts, 100)
+}
+
+pub fn part_two(starts: &[usize; 2]) -> Result<usize> {
+    let wins = Rules::new(BOARD, 21)?.dirac(*starts)?;
+    Ok(wins[0].max(wins[1]))
+}
+
+pub struct Day21;
+
+impl Puzzle for Day21 {
+    type Input = [usize; 2];
+
+    const DAY: usize = 21;
+    const TITLE: &'static str = "Dirac Dice";
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse(input)
+    }
+
+    fn part_one(input: &Self::Input) -> Result<Answer> {
+        Ok(part_one(input)?.into())
+    }
+
+    fn part_two(input: &Self::Input) -> Result<Option<Answer>> {
+        Ok(Some(part_two(input)?.into()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DATA: &str = "Player 1 starting position: 4\nPlayer 2 starting position: 8\n";
+
+    #[test]
+    fn test_deterministic() -> Result<()> {
+        assert_eq!(part_one(&parse(DATA)?)?, 739785);
+        Ok(())
+    }
+
+    #[test]
+    fn test_dirac() -> Result<()> {
+        assert_eq!(
+            Rules::new(10, 21)?.dirac(parse(DATA)?)?,
+            [444356092776315, 341960390180808]
+        );
+
+        // Player 1 wins every universe with the first roll of a 1 point game
+        assert_eq!(Rules::new(10, 1)?.dirac([4, 8])?, [27, 0]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_rules() -> Result<()> {
+        assert!(Rules::new(0, 21).is_err());
+
+        let rules = Rules::new(10, 21)?;
+        assert!(rules.deterministic([4, 8], 0).is_err());
+        assert!(rules.deterministic([4, 11], 6).is_err());
+        assert!(rules.dirac([0, 8]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_error() -> Result<()> {
+        let e = parse("Player 1 starting position: 4\nPlayer 2 starting position: x").unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            "line 2, column 29: invalid digit found in string"
+        );
+        assert!(parse("Player 1 starting position: 4").is_err());
+
+        let e = parse("Player 1 starting position: 4\nPlayer 2 starting position: 11").unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            "line 2, column 29: Positions go from 1 to 10"
+        );
+        Ok(())
+    }
+}
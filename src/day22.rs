@@ -0,0 +1,202 @@
+use crate::error::{column_of, PuzzleError, Result};
+use crate::{parse, Answer, Puzzle};
+use std::str::FromStr;
+
+/// A box of cubes from `min` to `max` on every axis, both inclusive
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Cuboid {
+    pub min: [isize; 3],
+    pub max: [isize; 3],
+}
+
+impl Cuboid {
+    pub fn new(min: [isize; 3], max: [isize; 3]) -> Cuboid {
+        Cuboid { min, max }
+    }
+
+    /// The cubes in both cuboids, if there are any
+    pub fn intersection(&self, other: &Cuboid) -> Option<Cuboid> {
+        let min = [0, 1, 2].map(|i| self.min[i].max(other.min[i]));
+        let max = [0, 1, 2].map(|i| self.max[i].min(other.max[i]));
+
+        if (0..3).all(|i| min[i] <= max[i]) {
+            Some(Cuboid { min, max })
+        } else {
+            None
+        }
+    }
+
+    /// The number of cubes in the cuboid
+    pub fn volume(&self) -> usize {
+        (0..3)
+            .map(|i| (self.max[i] - self.min[i] + 1) as usize)
+            .product()
+    }
+}
+
+/// Parses cuboids like `x=10..12,y=10..12,z=10..12`
+impl FromStr for Cuboid {
+    type Err = PuzzleError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let ranges = s.split(',').collect::<Vec<_>>();
+        if ranges.len() != 3 {
+            return Err(PuzzleError::at(
+                0,
+                s,
+                s,
+                "Expected a cuboid like x=1..2,y=3..4,z=5..6",
+            ));
+        }
+
+        let mut cuboid = Cuboid::new([0; 3], [0; 3]);
+        for (i, (range, axis)) in ranges.into_iter().zip(["x", "y", "z"]).enumerate() {
+            let range = parse::range(s, range, axis)?;
+            cuboid.min[i] = *range.start();
+            cuboid.max[i] = *range.end();
+        }
+
+        Ok(cuboid)
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Step {
+    pub on: bool,
+    pub cuboid: Cuboid,
+}
+
+impl FromStr for Step {
+    type Err = PuzzleError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (on, cuboid) = s.split_once(' ').ok_or_else(|| {
+            PuzzleError::at(0, s, s, "Expected a step like on x=1..2,y=3..4,z=5..6")
+        })?;
+
+        let on = match on {
+            "on" => true,
+            "off" => false,
+            _ => return Err(PuzzleError::at(0, s, on, "Expected on or off")),
+        };
+        let cuboid = Cuboid::from_str(cuboid).map_err(|e| e.shift(1, column_of(s, cuboid)))?;
+
+        Ok(Step { on, cuboid })
+    }
+}
+
+/// The number of lit cubes after all the steps, counted inside `region` if
+/// there is one. Every cuboid that is added also adds its intersections with
+/// cuboids before it with the opposite sign, so overlaps are counted once
+/// and turning cubes off subtracts them.
+pub fn lit(steps: &[Step], region: Option<&Cuboid>) -> usize {
+    let mut signed: Vec<(Cuboid, isize)> = Vec::new();
+
+    for step in steps {
+        let cuboid = match region {
+            Some(region) => match step.cuboid.intersection(region) {
+                Some(cuboid) => cuboid,
+                None => continue,
+            },
+            None => step.cuboid,
+        };
+
+        let overlaps = signed
+            .iter()
+            .filter_map(|(other, sign)| cuboid.intersection(other).map(|i| (i, -sign)))
+            .collect::<Vec<_>>();
+        signed.extend(overlaps);
+
+        if step.on {
+            signed.push((cuboid, 1));
+        }
+    }
+
+    let total = signed
+        .iter()
+        .map(|(cuboid, sign)| cuboid.volume() as isize * sign)
+        .sum::<isize>();
+    total as usize
+}
+
+pub fn parse(input: &str) -> Result<Vec<Step>> {
+    parse::each_line(input)
+}
+
+pub fn part_one(steps: &[Step]) -> usize {
+    lit(steps, Some(&Cuboid::new([-50; 3], [50; 3])))
+}
+
+pub fn part_two(steps: &[Step]) -> usize {
+    lit(steps, None)
+}
+
+pub struct Day22;
+
+impl Puzzle for Day22 {
+    type Input = Vec<Step>;
+
+    const DAY: usize = 22;
+    const TITLE: &'static str = "Reactor Reboot";
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse(input)
+    }
+
+    fn part_one(input: &Self::Input) -> Result<Answer> {
+        Ok(part_one(input).into())
+    }
+
+    fn part_two(input: &Self::Input) -> Result<Option<Answer>> {
+        Ok(Some(part_two(input).into()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DATA: &str = "on x=10..12,y=10..12,z=10..12
+on x=11..13,y=11..13,z=11..13
+off x=9..11,y=9..11,z=9..11
+on x=10..10,y=10..10,z=10..10
+";
+
+    #[test]
+    fn test_cuboid() -> Result<()> {
+        let a = Cuboid::new([0, 0, 0], [2, 2, 2]);
+        let b = Cuboid::new([1, 2, -5], [4, 4, 0]);
+
+        assert_eq!(a.volume(), 27);
+        assert_eq!(a.intersection(&b), Some(Cuboid::new([1, 2, 0], [2, 2, 0])));
+        assert_eq!(a.intersection(&Cuboid::new([3, 0, 0], [3, 0, 0])), None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_lit() -> Result<()> {
+        let steps = parse(DATA)?;
+        assert_eq!(lit(&steps[..1], None), 27);
+        assert_eq!(lit(&steps[..2], None), 46);
+        assert_eq!(lit(&steps[..3], None), 38);
+        assert_eq!(lit(&steps, None), 39);
+
+        // Only the far corner of the first cuboid is inside the region
+        let region = Cuboid::new([12; 3], [20; 3]);
+        assert_eq!(lit(&steps[..1], Some(&region)), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_error() -> Result<()> {
+        let e = parse("on x=1..2,y=3..4,z=5..6\nof x=1..2,y=3..4,z=5..6").unwrap_err();
+        assert_eq!(e.to_string(), "line 2, column 1: Expected on or off");
+
+        let e = parse("off x=1..2,y=3..a,z=5..6").unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            "line 1, column 17: invalid digit found in string"
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,338 @@
+use crate::error::{PuzzleError, Result};
+use crate::search::{self, Path};
+use crate::{parse, Answer, Puzzle};
+use std::fmt;
+
+const HALLWAY: usize = 11;
+const ROOMS: usize = 4;
+
+/// The lines part two unfolds between the first and second row of the rooms
+const UNFOLDED: [&str; 2] = ["  #D#C#B#A#", "  #D#B#A#C#"];
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Amphipod {
+    Amber,
+    Bronze,
+    Copper,
+    Desert,
+}
+
+impl Amphipod {
+    fn from_char(c: char) -> Option<Amphipod> {
+        match c {
+            'A' => Some(Amphipod::Amber),
+            'B' => Some(Amphipod::Bronze),
+            'C' => Some(Amphipod::Copper),
+            'D' => Some(Amphipod::Desert),
+            _ => None,
+        }
+    }
+
+    fn to_char(self) -> char {
+        match self {
+            Amphipod::Amber => 'A',
+            Amphipod::Bronze => 'B',
+            Amphipod::Copper => 'C',
+            Amphipod::Desert => 'D',
+        }
+    }
+
+    /// The energy it takes to move one step
+    pub fn energy(&self) -> usize {
+        match self {
+            Amphipod::Amber => 1,
+            Amphipod::Bronze => 10,
+            Amphipod::Copper => 100,
+            Amphipod::Desert => 1000,
+        }
+    }
+
+    /// The room the amphipod belongs in, counting from the left
+    pub fn room(&self) -> usize {
+        *self as usize
+    }
+}
+
+/// The hallway, and the rooms from the top down
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Burrow {
+    hallway: [Option<Amphipod>; HALLWAY],
+    rooms: [Vec<Option<Amphipod>>; ROOMS],
+}
+
+/// The hallway space right outside a room, where amphipods can't stop
+fn entrance(room: usize) -> usize {
+    2 + 2 * room
+}
+
+impl Burrow {
+    pub fn depth(&self) -> usize {
+        self.rooms[0].len()
+    }
+
+    fn is_organized(&self) -> bool {
+        self.rooms.iter().enumerate().all(|(room, spaces)| {
+            spaces
+                .iter()
+                .all(|space| space.is_some_and(|a| a.room() == room))
+        })
+    }
+
+    /// Whether the room holds nothing but the amphipods that belong there
+    fn is_settled(&self, room: usize) -> bool {
+        self.rooms[room]
+            .iter()
+            .flatten()
+            .all(|amphipod| amphipod.room() == room)
+    }
+
+    /// Whether the hallway between `from` and `to` is empty, not counting
+    /// `from` itself
+    fn is_clear(&self, from: usize, to: usize) -> bool {
+        let between = if from < to {
+            from + 1..=to
+        } else {
+            to..=from - 1
+        };
+        between.into_iter().all(|x| self.hallway[x].is_none())
+    }
+
+    /// Every burrow one move away, with the energy the move takes. An
+    /// amphipod either leaves its room for the hallway or goes from the
+    /// hallway into the room it belongs in.
+    fn moves(&self) -> Vec<(Burrow, usize)> {
+        let mut moves = Vec::new();
+
+        for (x, space) in self.hallway.iter().enumerate() {
+            let Some(amphipod) = space else {
+                continue;
+            };
+            let room = amphipod.room();
+            if !self.is_settled(room) || !self.is_clear(x, entrance(room)) {
+                continue;
+            }
+
+            if let Some(depth) = self.rooms[room].iter().rposition(Option::is_none) {
+                let mut next = self.clone();
+                next.hallway[x] = None;
+                next.rooms[room][depth] = Some(*amphipod);
+                let steps = x.abs_diff(entrance(room)) + depth + 1;
+                moves.push((next, steps * amphipod.energy()));
+            }
+        }
+
+        for room in 0..ROOMS {
+            if self.is_settled(room) {
+                continue;
+            }
+            let Some((depth, amphipod)) = self.rooms[room]
+                .iter()
+                .enumerate()
+                .find_map(|(depth, space)| space.map(|a| (depth, a)))
+            else {
+                continue;
+            };
+
+            for x in 0..HALLWAY {
+                let stops_at_entrance = (0..ROOMS).any(|r| entrance(r) == x);
+                if stops_at_entrance || !self.is_clear(entrance(room), x) {
+                    continue;
+                }
+
+                let mut next = self.clone();
+                next.rooms[room][depth] = None;
+                next.hallway[x] = Some(amphipod);
+                let steps = x.abs_diff(entrance(room)) + depth + 1;
+                moves.push((next, steps * amphipod.energy()));
+            }
+        }
+
+        moves
+    }
+
+    /// The burrow with the two lines from the folded part of the diagram
+    /// added after the first row of the rooms
+    pub fn unfold(&self) -> Burrow {
+        let mut unfolded = self.clone();
+        for (row, line) in UNFOLDED.iter().enumerate() {
+            for (room, spaces) in unfolded.rooms.iter_mut().enumerate() {
+                let c = line.chars().nth(3 + 2 * room).unwrap_or('.');
+                spaces.insert(row + 1, Amphipod::from_char(c));
+            }
+        }
+        unfolded
+    }
+
+    /// The cheapest way to organize the burrow, where the cost is the energy
+    /// spent
+    pub fn organize(&self) -> Option<Path<Burrow>> {
+        search::dijkstra(self.clone(), Burrow::moves, Burrow::is_organized)
+    }
+}
+
+/// Draws the burrow the way the puzzle input does
+impl fmt::Display for Burrow {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let space = |s: &Option<Amphipod>| s.map_or('.', Amphipod::to_char);
+
+        writeln!(f, "{}", "#".repeat(HALLWAY + 2))?;
+        writeln!(
+            f,
+            "#{}#",
+            self.hallway.iter().map(space).collect::<String>()
+        )?;
+        for depth in 0..self.depth() {
+            let row = self
+                .rooms
+                .iter()
+                .map(|spaces| space(&spaces[depth]).to_string())
+                .collect::<Vec<_>>()
+                .join("#");
+            if depth == 0 {
+                writeln!(f, "###{}###", row)?;
+            } else {
+                writeln!(f, "  #{}#", row)?;
+            }
+        }
+        writeln!(f, "  {}", "#".repeat(2 * ROOMS + 1))
+    }
+}
+
+/// Prints every burrow of a path found by `Burrow::organize` with the energy
+/// spent so far
+pub fn print_moves(path: &Path<Burrow>) -> String {
+    path.steps
+        .iter()
+        .map(|(energy, burrow)| format!("Energy {}:\n{}", energy, burrow))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+pub fn parse(input: &str) -> Result<Burrow> {
+    let lines = parse::lines(input).collect::<Vec<_>>();
+    if lines.len() < 4 {
+        return Err(PuzzleError::invalid(
+            "Expected a wall, the hallway, at least one row of rooms and a wall",
+        ));
+    }
+
+    let space = |n: usize, line: &str, column: usize| match line.chars().nth(column) {
+        Some('.') => Ok(None),
+        Some(c) => Amphipod::from_char(c).map(Some).ok_or_else(|| {
+            PuzzleError::parse(
+                n,
+                column + 1,
+                format!("Expected an amphipod or '.', found {:?}", c),
+            )
+        }),
+        None => Err(PuzzleError::parse(
+            n,
+            column + 1,
+            "Expected an amphipod or '.', found the end of the line",
+        )),
+    };
+
+    let (n, line) = lines[1];
+    let mut hallway = [None; HALLWAY];
+    for (x, h) in hallway.iter_mut().enumerate() {
+        *h = space(n, line, x + 1)?;
+    }
+
+    let mut rooms: [Vec<Option<Amphipod>>; ROOMS] = Default::default();
+    for (n, line) in &lines[2..lines.len() - 1] {
+        for (room, spaces) in rooms.iter_mut().enumerate() {
+            spaces.push(space(*n, line, 3 + 2 * room)?);
+        }
+    }
+
+    Ok(Burrow { hallway, rooms })
+}
+
+fn least_energy(burrow: &Burrow) -> Result<usize> {
+    burrow
+        .organize()
+        .map(|path| path.cost)
+        .ok_or_else(|| PuzzleError::no_solution("The amphipods can't be organized"))
+}
+
+pub fn part_one(burrow: &Burrow) -> Result<usize> {
+    least_energy(burrow)
+}
+
+pub fn part_two(burrow: &Burrow) -> Result<usize> {
+    least_energy(&burrow.unfold())
+}
+
+pub struct Day23;
+
+impl Puzzle for Day23 {
+    type Input = Burrow;
+
+    const DAY: usize = 23;
+    const TITLE: &'static str = "Amphipod";
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse(input)
+    }
+
+    fn part_one(input: &Self::Input) -> Result<Answer> {
+        Ok(part_one(input)?.into())
+    }
+
+    fn part_two(input: &Self::Input) -> Result<Option<Answer>> {
+        Ok(Some(part_two(input)?.into()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DATA: &str = "\
+#############
+#...........#
+###B#C#B#D###
+  #A#D#C#A#
+  #########
+";
+
+    #[test]
+    fn test_display() -> Result<()> {
+        let burrow = parse(DATA)?;
+        assert_eq!(burrow.to_string(), DATA);
+        assert_eq!(burrow.unfold().depth(), 4);
+        assert_eq!(parse(&burrow.unfold().to_string())?, burrow.unfold());
+        Ok(())
+    }
+
+    #[test]
+    fn test_organize() -> Result<()> {
+        // Only the A and the D at the bottom of the first and last room are
+        // in the wrong place, but they have to wait for the rooms to empty
+        let burrow =
+            parse("#############\n#...........#\n###A#B#C#D###\n  #D#B#C#A#\n  #########\n")?;
+        let path = burrow.organize().unwrap();
+
+        assert_eq!(path.steps.first().map(|(energy, _)| *energy), Some(0));
+        assert!(path.goal().is_some_and(Burrow::is_organized));
+        assert!(print_moves(&path).starts_with("Energy 0:\n#############\n"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_part_one() -> Result<()> {
+        assert_eq!(part_one(&parse(DATA)?)?, 12521);
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_error() -> Result<()> {
+        let e = parse(&DATA.replace("#A#D", "#A#E")).unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            "line 4, column 6: Expected an amphipod or '.', found 'E'"
+        );
+        assert!(parse("#############\n#...........#\n").is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,403 @@
+use crate::error::{column_of, PuzzleError, Result};
+use crate::{parse, Answer, Puzzle};
+use std::str::FromStr;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Register {
+    W,
+    X,
+    Y,
+    Z,
+}
+
+impl FromStr for Register {
+    type Err = PuzzleError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "w" => Ok(Register::W),
+            "x" => Ok(Register::X),
+            "y" => Ok(Register::Y),
+            "z" => Ok(Register::Z),
+            _ => Err(PuzzleError::parse(
+                1,
+                1,
+                format!("Expected a register w, x, y or z, found {:?}", s),
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Operand {
+    Register(Register),
+    Number(isize),
+}
+
+impl FromStr for Operand {
+    type Err = PuzzleError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Ok(register) = Register::from_str(s) {
+            return Ok(Operand::Register(register));
+        }
+        s.parse().map(Operand::Number).map_err(|_| {
+            PuzzleError::parse(
+                1,
+                1,
+                format!("Expected a register or a number, found {:?}", s),
+            )
+        })
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Instruction {
+    Inp(Register),
+    Add(Register, Operand),
+    Mul(Register, Operand),
+    Div(Register, Operand),
+    Mod(Register, Operand),
+    Eql(Register, Operand),
+}
+
+impl FromStr for Instruction {
+    type Err = PuzzleError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let fields = s.split_whitespace().collect::<Vec<_>>();
+        let register =
+            |field: &str| Register::from_str(field).map_err(|e| e.shift(1, column_of(s, field)));
+        let operand =
+            |field: &str| Operand::from_str(field).map_err(|e| e.shift(1, column_of(s, field)));
+
+        match fields[..] {
+            ["inp", a] => Ok(Instruction::Inp(register(a)?)),
+            ["add", a, b] => Ok(Instruction::Add(register(a)?, operand(b)?)),
+            ["mul", a, b] => Ok(Instruction::Mul(register(a)?, operand(b)?)),
+            ["div", a, b] => Ok(Instruction::Div(register(a)?, operand(b)?)),
+            ["mod", a, b] => Ok(Instruction::Mod(register(a)?, operand(b)?)),
+            ["eql", a, b] => Ok(Instruction::Eql(register(a)?, operand(b)?)),
+            [op, ..] if ["inp", "add", "mul", "div", "mod", "eql"].contains(&op) => Err(
+                PuzzleError::at(0, s, s, format!("Wrong number of arguments for {}", op)),
+            ),
+            [op, ..] => Err(PuzzleError::at(
+                0,
+                s,
+                op,
+                format!("Unknown instruction {:?}", op),
+            )),
+            [] => Err(PuzzleError::at(0, s, s, "Expected an instruction")),
+        }
+    }
+}
+
+/// The four registers of the arithmetic logic unit
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct Alu {
+    registers: [isize; 4],
+}
+
+impl Alu {
+    pub fn get(&self, register: Register) -> isize {
+        self.registers[register as usize]
+    }
+
+    fn value(&self, operand: Operand) -> isize {
+        match operand {
+            Operand::Register(r) => self.get(r),
+            Operand::Number(n) => n,
+        }
+    }
+
+    /// Run `program` from the current state, reading `inp` values from
+    /// `inputs` in order
+    pub fn run(&mut self, program: &[Instruction], inputs: &[isize]) -> Result<()> {
+        let mut inputs = inputs.iter();
+
+        for (i, instruction) in program.iter().enumerate() {
+            let fail = |message: &str| {
+                PuzzleError::no_solution(format!("Instruction {}: {}", i + 1, message))
+            };
+
+            let (a, result) = match *instruction {
+                Instruction::Inp(a) => {
+                    let input = inputs.next().ok_or_else(|| fail("Ran out of input"))?;
+                    (a, *input)
+                }
+                Instruction::Add(a, b) => {
+                    let sum = self.get(a).checked_add(self.value(b));
+                    (a, sum.ok_or_else(|| fail("Addition overflows"))?)
+                }
+                Instruction::Mul(a, b) => {
+                    let product = self.get(a).checked_mul(self.value(b));
+                    (a, product.ok_or_else(|| fail("Multiplication overflows"))?)
+                }
+                Instruction::Div(a, b) => {
+                    let b = self.value(b);
+                    if b == 0 {
+                        return Err(fail("Division by zero"));
+                    }
+                    let quotient = self.get(a).checked_div(b);
+                    (a, quotient.ok_or_else(|| fail("Division overflows"))?)
+                }
+                Instruction::Mod(a, b) => {
+                    let (a_value, b) = (self.get(a), self.value(b));
+                    if a_value < 0 || b <= 0 {
+                        return Err(fail("Modulo with a negative number or zero"));
+                    }
+                    (a, a_value % b)
+                }
+                Instruction::Eql(a, b) => (a, (self.get(a) == self.value(b)) as isize),
+            };
+            self.registers[a as usize] = result;
+        }
+
+        Ok(())
+    }
+}
+
+/// Whether MONAD accepts the model number with these digits, which it does
+/// when it leaves 0 in z
+pub fn accepts(program: &[Instruction], digits: &[isize]) -> Result<bool> {
+    let mut alu = Alu::default();
+    alu.run(program, digits)?;
+    Ok(alu.get(Register::Z) == 0)
+}
+
+/// The number of instructions MONAD uses for every digit
+const BLOCK: usize = 18;
+
+// The instructions of a block that differ between digits: whether z is
+// divided by 1 or 26, what is added to x before comparing it to the digit,
+// and what is added to the digit before it is pushed
+const DIV: usize = 4;
+const CHECK: usize = 5;
+const OFFSET: usize = 15;
+
+/// Every digit either pushes `digit + offset` onto z, used as a stack of
+/// base 26 numbers, or pops a value and only avoids pushing again when the
+/// value plus `check` is the digit. Since there are as many pops as pushes
+/// z can only end at 0 if every pop avoids pushing, so every pop pairs with
+/// a push into a constraint `digits[pop] = digits[push] + difference`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Constraint {
+    pub push: usize,
+    pub pop: usize,
+    pub difference: isize,
+}
+
+fn number(instruction: &Instruction) -> Option<isize> {
+    match instruction {
+        Instruction::Div(_, Operand::Number(n)) | Instruction::Add(_, Operand::Number(n)) => {
+            Some(*n)
+        }
+        _ => None,
+    }
+}
+
+/// Read the constraints from the blocks of the program
+pub fn constraints(program: &[Instruction]) -> Result<Vec<Constraint>> {
+    let not_monad = |block: usize, message: &str| {
+        PuzzleError::no_solution(format!(
+            "Block {} is not like MONAD: {}",
+            block + 1,
+            message
+        ))
+    };
+
+    if program.is_empty() || !program.len().is_multiple_of(BLOCK) {
+        return Err(PuzzleError::no_solution(format!(
+            "MONAD has blocks of {} instructions, but there are {}",
+            BLOCK,
+            program.len()
+        )));
+    }
+
+    let blocks = program.chunks(BLOCK).collect::<Vec<_>>();
+    let mut stack = Vec::new();
+    let mut constraints = Vec::new();
+
+    for (i, block) in blocks.iter().enumerate() {
+        let differs = (0..BLOCK)
+            .filter(|j| ![DIV, CHECK, OFFSET].contains(j))
+            .any(|j| block[j] != blocks[0][j]);
+        if differs || blocks[0][0] != Instruction::Inp(Register::W) {
+            return Err(not_monad(i, "the instructions are different"));
+        }
+
+        let (div, check, offset) = match (
+            number(&block[DIV]),
+            number(&block[CHECK]),
+            number(&block[OFFSET]),
+        ) {
+            (Some(div), Some(check), Some(offset)) => (div, check, offset),
+            _ => {
+                return Err(not_monad(
+                    i,
+                    "expected numbers in the div and add instructions",
+                ))
+            }
+        };
+
+        match div {
+            1 => stack.push((i, offset)),
+            26 => {
+                let (push, offset) = stack
+                    .pop()
+                    .ok_or_else(|| not_monad(i, "it pops from an empty stack"))?;
+                constraints.push(Constraint {
+                    push,
+                    pop: i,
+                    difference: offset + check,
+                });
+            }
+            _ => return Err(not_monad(i, "z is divided by something other than 1 or 26")),
+        }
+    }
+
+    if !stack.is_empty() {
+        return Err(PuzzleError::no_solution(
+            "Some digits push onto z without being popped",
+        ));
+    }
+    Ok(constraints)
+}
+
+/// The largest or smallest digits that meet every constraint
+fn model_number(program: &[Instruction], largest: bool) -> Result<usize> {
+    let constraints = constraints(program)?;
+    let mut digits = vec![0; program.len() / BLOCK];
+
+    for c in &constraints {
+        if c.difference.abs() > 8 {
+            return Err(PuzzleError::no_solution(format!(
+                "Digits {} and {} can't differ by {}",
+                c.push + 1,
+                c.pop + 1,
+                c.difference
+            )));
+        }
+
+        let push = if largest {
+            9.min(9 - c.difference)
+        } else {
+            1.max(1 - c.difference)
+        };
+        digits[c.push] = push;
+        digits[c.pop] = push + c.difference;
+    }
+
+    // The analysis assumed the shape of MONAD, so check it with the real thing
+    if !accepts(program, &digits)? {
+        return Err(PuzzleError::no_solution(
+            "MONAD does not accept the model number found",
+        ));
+    }
+
+    Ok(digits.iter().fold(0, |n, digit| n * 10 + *digit as usize))
+}
+
+pub fn parse(input: &str) -> Result<Vec<Instruction>> {
+    parse::each_line(input)
+}
+
+pub fn part_one(program: &[Instruction]) -> Result<usize> {
+    model_number(program, true)
+}
+
+pub fn part_two(program: &[Instruction]) -> Result<usize> {
+    model_number(program, false)
+}
+
+pub struct Day24;
+
+impl Puzzle for Day24 {
+    type Input = Vec<Instruction>;
+
+    const DAY: usize = 24;
+    const TITLE: &'static str = "Arithmetic Logic Unit";
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse(input)
+    }
+
+    fn part_one(input: &Self::Input) -> Result<Answer> {
+        Ok(part_one(input)?.into())
+    }
+
+    fn part_two(input: &Self::Input) -> Result<Option<Answer>> {
+        Ok(Some(part_two(input)?.into()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::monad_block;
+
+    #[test]
+    fn test_run() -> Result<()> {
+        // Stores the lowest four bits of the input in w, x, y and z
+        let program = parse(
+            "inp w\nadd z w\nmod z 2\ndiv w 2\nadd y w\nmod y 2\ndiv w 2\n\
+             add x w\nmod x 2\ndiv w 2\nmod w 2",
+        )?;
+        let mut alu = Alu::default();
+        alu.run(&program, &[13])?;
+
+        let bits = [Register::W, Register::X, Register::Y, Register::Z].map(|r| alu.get(r));
+        assert_eq!(bits, [1, 1, 0, 1]);
+
+        assert!(Alu::default().run(&program, &[]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_overflow() -> Result<()> {
+        let squares = parse(&format!("inp z\n{}", "mul z z\n".repeat(7)))?;
+        let e = Alu::default().run(&squares, &[9]).unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            "no solution: Instruction 6: Multiplication overflows"
+        );
+
+        let sum = parse("inp z\nadd z z")?;
+        assert!(Alu::default().run(&sum, &[isize::MAX]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_model_number() -> Result<()> {
+        let program = parse(&(monad_block(1, 12, 4) + &monad_block(26, -6, 3)))?;
+
+        // Every accepted number, found by trying them all
+        let accepted = (1..=9)
+            .flat_map(|a| (1..=9).map(move |b| [a, b]))
+            .filter(|digits| accepts(&program, digits).unwrap())
+            .map(|[a, b]| (a * 10 + b) as usize)
+            .collect::<Vec<_>>();
+
+        assert_eq!(part_one(&program)?, 97);
+        assert_eq!(part_two(&program)?, 31);
+        assert_eq!(accepted.iter().max(), Some(&97));
+        assert_eq!(accepted.iter().min(), Some(&31));
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_error() -> Result<()> {
+        let e = parse("inp w\nadd x q").unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            "line 2, column 7: Expected a register or a number, found \"q\""
+        );
+
+        let e = parse("inp w\nsub x 1").unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            "line 2, column 1: Unknown instruction \"sub\""
+        );
+        Ok(())
+    }
+}
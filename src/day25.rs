@@ -0,0 +1,216 @@
+use crate::error::{PuzzleError, Result};
+use crate::{parse, Answer, Puzzle};
+use std::collections::HashSet;
+use std::fmt;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Cell {
+    Empty,
+    East,
+    South,
+}
+
+/// The sea cucumbers on a grid where moving off one edge wraps around to the
+/// opposite edge
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SeaFloor {
+    width: usize,
+    height: usize,
+    cells: Vec<Cell>,
+}
+
+impl SeaFloor {
+    /// Move every sea cucumber of the herd that has room in front of it,
+    /// all at once. Returns how many moved.
+    fn move_herd(&mut self, herd: Cell) -> usize {
+        let (dx, dy) = if herd == Cell::East { (1, 0) } else { (0, 1) };
+
+        let moving = (0..self.cells.len())
+            .filter_map(|i| {
+                let (x, y) = (i % self.width, i / self.width);
+                let ahead = (y + dy) % self.height * self.width + (x + dx) % self.width;
+                (self.cells[i] == herd && self.cells[ahead] == Cell::Empty).then_some((i, ahead))
+            })
+            .collect::<Vec<_>>();
+
+        for (from, to) in &moving {
+            self.cells[*from] = Cell::Empty;
+            self.cells[*to] = herd;
+        }
+        moving.len()
+    }
+
+    /// The sea floor after one step, where the east-facing herd moves first,
+    /// and the number of sea cucumbers that moved
+    pub fn step(&self) -> (SeaFloor, usize) {
+        let mut next = self.clone();
+        let moved = next.move_herd(Cell::East) + next.move_herd(Cell::South);
+        (next, moved)
+    }
+
+    /// Every step from here on, which never ends even when the sea cucumbers
+    /// have stopped moving
+    pub fn steps(&self) -> Steps {
+        Steps {
+            floor: self.clone(),
+        }
+    }
+}
+
+pub struct Steps {
+    floor: SeaFloor,
+}
+
+impl Iterator for Steps {
+    type Item = (SeaFloor, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (floor, moved) = self.floor.step();
+        self.floor = floor.clone();
+        Some((floor, moved))
+    }
+}
+
+/// Draws the sea floor the way the puzzle input does
+impl fmt::Display for SeaFloor {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for row in self.cells.chunks(self.width) {
+            let line = row
+                .iter()
+                .map(|cell| match cell {
+                    Cell::Empty => '.',
+                    Cell::East => '>',
+                    Cell::South => 'v',
+                })
+                .collect::<String>();
+            writeln!(f, "{}", line)?;
+        }
+        Ok(())
+    }
+}
+
+pub fn parse(input: &str) -> Result<SeaFloor> {
+    let mut cells = Vec::new();
+    let mut width = None;
+    let mut height = 0;
+
+    for (n, line) in parse::lines(input) {
+        if *width.get_or_insert(line.chars().count()) != line.chars().count() {
+            return Err(PuzzleError::parse(n, 1, "Rows have different lengths"));
+        }
+
+        for (x, c) in line.chars().enumerate() {
+            cells.push(match c {
+                '.' => Cell::Empty,
+                '>' => Cell::East,
+                'v' => Cell::South,
+                _ => {
+                    return Err(PuzzleError::parse(
+                        n,
+                        x + 1,
+                        format!("{:?} is not a sea cucumber", c),
+                    ))
+                }
+            });
+        }
+        height += 1;
+    }
+
+    let width = width.ok_or_else(|| PuzzleError::invalid("The sea floor is empty"))?;
+    Ok(SeaFloor {
+        width,
+        height,
+        cells,
+    })
+}
+
+/// The first step on which no sea cucumber moves. A herd can also keep
+/// going around in circles, which shows as the sea floor repeating.
+pub fn part_one(floor: &SeaFloor) -> Result<usize> {
+    let mut seen = HashSet::from([floor.clone()]);
+
+    for (i, (floor, moved)) in floor.steps().enumerate() {
+        if moved == 0 {
+            return Ok(i + 1);
+        }
+        if !seen.insert(floor) {
+            break;
+        }
+    }
+
+    Err(PuzzleError::no_solution(
+        "The sea cucumbers never stop moving",
+    ))
+}
+
+pub struct Day25;
+
+impl Puzzle for Day25 {
+    type Input = SeaFloor;
+
+    const DAY: usize = 25;
+    const TITLE: &'static str = "Sea Cucumber";
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse(input)
+    }
+
+    fn part_one(input: &Self::Input) -> Result<Answer> {
+        Ok(part_one(input)?.into())
+    }
+
+    fn part_two(_: &Self::Input) -> Result<Option<Answer>> {
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DATA: &str = "\
+v...>>.vv>
+.vv>>.vv..
+>>.>v>...v
+>>v>>.>.v.
+v>v.vv.v..
+>.>>..v...
+.vv..>.>v.
+v.v..>>v.v
+....v..v.>
+";
+
+    #[test]
+    fn test_steps() -> Result<()> {
+        let floor = parse("...>>>>>...")?;
+        let steps = floor
+            .steps()
+            .take(2)
+            .map(|(floor, _)| floor.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(steps, vec!["...>>>>.>..\n", "...>>>.>.>.\n"]);
+
+        // The east-facing herd moves first, making room for the one below it
+        let (floor, moved) = parse("..........\n.>v....v..\n.......>..\n..........")?.step();
+        assert_eq!(moved, 3);
+        assert_eq!(
+            floor.to_string(),
+            "..........\n.>........\n..v....v>.\n..........\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_part_one() -> Result<()> {
+        assert_eq!(part_one(&parse(DATA)?)?, 58);
+        assert!(part_one(&parse("...>>>>>...")?).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_error() -> Result<()> {
+        let e = parse("v..\n.<.").unwrap_err();
+        assert_eq!(e.to_string(), "line 2, column 2: '<' is not a sea cucumber");
+        Ok(())
+    }
+}
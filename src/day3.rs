@@ -1,62 +1,86 @@
-use anyhow::Result;
-use std::fs;
-use std::path::Path;
+use crate::error::{PuzzleError, Result};
+use crate::{parse, Answer, Puzzle};
 
-fn part_two(data: &Vec<&str>) -> usize {
-    let decimal_data = data
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Report {
+    n_col: usize,
+    numbers: Vec<usize>,
+}
+
+pub fn parse(input: &str) -> Result<Report> {
+    let data = parse::lines(input).collect::<Vec<_>>();
+    let numbers = data
         .iter()
-        .map(|line| usize::from_str_radix(line, 2).unwrap());
+        .map(|(n, line)| {
+            usize::from_str_radix(line, 2).map_err(|e| PuzzleError::at(n - 1, line, line, e))
+        })
+        .collect::<Result<Vec<usize>>>()?;
+
+    let n_col = data
+        .first()
+        .map(|(_, line)| line.len())
+        .ok_or_else(|| PuzzleError::invalid("The report is empty"))?;
 
-    let n_col: usize = data[0].len();
+    Ok(Report { n_col, numbers })
+}
+
+pub fn part_two(report: &Report) -> Result<usize> {
+    let n_col = report.n_col;
 
-    let mut o2: Vec<usize> = decimal_data.clone().collect();
+    let mut o2: Vec<usize> = report.numbers.clone();
 
     for i in (0..n_col).rev() {
         if o2.len() < 2 {
             break;
         }
         let ones = o2.iter().filter(|d| has_bit(d, &i)).count();
-        let half = (o2.len() + 1) / 2;
+        // Keep everything when every number has the same bit here
+        if ones == 0 || ones == o2.len() {
+            continue;
+        }
+        let half = o2.len().div_ceil(2);
         if ones >= half {
             o2.retain(|v| has_bit(v, &i));
         } else {
-            o2.retain(|v| has_bit(v, &i) == false);
+            o2.retain(|v| !has_bit(v, &i));
         }
     }
 
-    let mut co2: Vec<usize> = decimal_data.clone().collect();
+    let mut co2: Vec<usize> = report.numbers.clone();
 
     for i in (0..n_col).rev() {
         if co2.len() < 2 {
             break;
         }
-        let zeros = co2.iter().filter(|d| has_bit(d, &i) == false).count();
-        let half = (co2.len() + 1) / 2;
+        let zeros = co2.iter().filter(|d| !has_bit(d, &i)).count();
+        if zeros == 0 || zeros == co2.len() {
+            continue;
+        }
+        let half = co2.len().div_ceil(2);
         if zeros <= half {
-            co2.retain(|v| has_bit(v, &i) == false);
+            co2.retain(|v| !has_bit(v, &i));
         } else {
             co2.retain(|v| has_bit(v, &i));
         }
     }
 
-    o2[0] * co2[0]
+    match (o2.first(), co2.first()) {
+        (Some(o2), Some(co2)) => Ok(o2 * co2),
+        _ => Err(PuzzleError::no_solution("The report is empty")),
+    }
 }
 
 fn has_bit(d: &usize, i: &usize) -> bool {
     d & 1 << i > 0
 }
 
-fn part_one(data: &Vec<&str>) -> usize {
-    let decimal_data = data
-        .iter()
-        .map(|line| usize::from_str_radix(line, 2).unwrap());
+pub fn part_one(report: &Report) -> usize {
+    let half = report.numbers.len() / 2;
 
-    let half = decimal_data.len() / 2;
-
-    let n_col: usize = data[0].len();
+    let n_col = report.n_col;
 
     let (gamma, epsilon) = (0..n_col).rev().fold((0, 0), |(gamma, epsilon), i| {
-        let ones = decimal_data.clone().filter(|d| has_bit(d, &i)).count();
+        let ones = report.numbers.iter().filter(|d| has_bit(d, &i)).count();
         if ones > half {
             (gamma | 1 << i, epsilon)
         } else {
@@ -67,11 +91,25 @@ fn part_one(data: &Vec<&str>) -> usize {
     gamma * epsilon
 }
 
-pub fn main(path: &Path) -> Result<(usize, Option<usize>)> {
-    let input = fs::read_to_string(path).expect("Failed to read");
-    let data = input.lines().collect::<Vec<&str>>();
+pub struct Day3;
+
+impl Puzzle for Day3 {
+    type Input = Report;
 
-    Ok((part_one(&data), Some(part_two(&data))))
+    const DAY: usize = 3;
+    const TITLE: &'static str = "Binary Diagnostic";
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse(input)
+    }
+
+    fn part_one(input: &Self::Input) -> Result<Answer> {
+        Ok(part_one(input).into())
+    }
+
+    fn part_two(input: &Self::Input) -> Result<Option<Answer>> {
+        Ok(Some(part_two(input)?.into()))
+    }
 }
 
 #[cfg(test)]
@@ -82,4 +120,11 @@ mod tests {
     fn test() -> Result<()> {
         Ok(())
     }
+
+    #[test]
+    fn test_part_two_shared_bit() -> Result<()> {
+        // Both numbers start with 1, so the CO2 rule has no 0s to keep there
+        assert_eq!(part_two(&parse("10\n11")?)?, 6);
+        Ok(())
+    }
 }
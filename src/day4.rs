@@ -1,65 +1,64 @@
-use anyhow::{anyhow, Error, Result};
-use std::fs;
-use std::path::Path;
+use crate::error::{PuzzleError, Result};
+use crate::parse;
+use crate::{Answer, Puzzle};
+use std::fmt;
 
 #[derive(Debug, Clone)]
-struct Board {
+pub struct Board {
     size: usize,
     grid: Vec<Vec<(usize, bool)>>,
 }
 
-impl ToString for Board {
-    fn to_string(&self) -> String {
-        let mut repr = String::from("");
+impl fmt::Display for Board {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         for row in 0..self.size {
             for col in 0..self.size {
                 let (v, marked) = &self.grid[row][col];
-                repr.push_str(&format!("({}, {})", v, marked));
+                write!(f, "({}, {})", v, marked)?;
             }
-            repr.push_str("|");
+            write!(f, "|")?;
         }
-        repr
+        Ok(())
     }
 }
 
-#[derive(Debug, Clone, Copy)]
-enum Direction {
-    Column,
-    Row,
-}
-
 impl Board {
-    fn new(board_rep: &Vec<&str>) -> Board {
+    fn new(board_rep: &[&str]) -> Result<Board> {
         let size = board_rep.len();
 
         let grid = board_rep
             .iter()
-            .map(|x| {
-                x.split_whitespace()
-                    .map(|y| (y.parse::<usize>().unwrap(), false))
-                    .collect::<Vec<_>>()
+            .enumerate()
+            .map(|(i, x)| {
+                let row = x
+                    .split_whitespace()
+                    .map(|y| {
+                        y.parse::<usize>()
+                            .map(|v| (v, false))
+                            .map_err(|e| PuzzleError::at(i, x, y, e))
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+
+                if row.len() != size {
+                    return Err(PuzzleError::at(
+                        i,
+                        x,
+                        x,
+                        format!("Expected {} numbers in a row of the board", size),
+                    ));
+                }
+                Ok(row)
             })
-            .collect::<Vec<Vec<(usize, bool)>>>();
+            .collect::<Result<Vec<Vec<(usize, bool)>>>>()?;
 
-        Board { size, grid }
+        Ok(Board { size, grid })
     }
 
-    fn iterate_over(
-        &self,
-        direction: Direction,
-    ) -> impl Iterator<Item = (usize, bool, (usize, usize))> + '_ {
+    fn iterate(&self) -> impl Iterator<Item = (usize, bool, (usize, usize))> + '_ {
         (0..self.size).flat_map(move |row| {
             (0..self.size).map(move |col| {
-                match direction {
-                    Direction::Row => {
-                        let (v, b) = self.grid[row][col];
-                        return (v, b, (row, col));
-                    }
-                    Direction::Column => {
-                        let (v, b) = self.grid[col][row];
-                        return (v, b, (row, col));
-                    }
-                };
+                let (v, b) = self.grid[row][col];
+                (v, b, (row, col))
             })
         })
     }
@@ -70,7 +69,7 @@ impl Board {
     }
 
     fn pos(&self, num: &usize) -> Option<(usize, usize)> {
-        for (v, _, (row, col)) in self.iterate_over(Direction::Row) {
+        for (v, _, (row, col)) in self.iterate() {
             if v == *num {
                 return Some((row, col));
             }
@@ -111,10 +110,10 @@ impl Board {
     }
 
     fn sum_unmarked(&self) -> usize {
-        self.iterate_over(Direction::Row).fold(
+        self.iterate().fold(
             0,
             |acc, (v, marked, (_, _))| {
-                if marked == false {
+                if !marked {
                     acc + v
                 } else {
                     acc
@@ -124,7 +123,7 @@ impl Board {
     }
 }
 
-fn find_bingo_board(boards: &Vec<Board>) -> Option<&Board> {
+fn find_bingo_board(boards: &[Board]) -> Option<&Board> {
     let mut bingo_board = None;
     for board in boards.iter() {
         if bingo_board.is_some() {
@@ -138,20 +137,43 @@ fn find_bingo_board(boards: &Vec<Board>) -> Option<&Board> {
     bingo_board
 }
 
-fn part_one(boards: &Vec<Board>, inputs: &Vec<usize>) -> Result<usize, Error> {
-    let mut boards = boards.clone();
+#[derive(Debug, Clone)]
+pub struct Bingo {
+    numbers: Vec<usize>,
+    boards: Vec<Board>,
+}
+
+pub fn parse(read_data: &str) -> Result<Bingo> {
+    let sections = parse::sections(read_data);
+    let ((line, numbers), boards) = sections
+        .split_first()
+        .ok_or_else(|| PuzzleError::invalid("Missing the numbers to draw"))?;
+
+    let numbers = parse::comma_list(numbers.trim_end()).map_err(|e| e.shift(*line, 1))?;
+
+    let boards = boards
+        .iter()
+        .map(|(line, section)| {
+            let rows = section.lines().collect::<Vec<_>>();
+            Board::new(&rows).map_err(|e| e.shift(*line, 1))
+        })
+        .collect::<Result<Vec<Board>>>()?;
+
+    Ok(Bingo { numbers, boards })
+}
+
+pub fn part_one(Bingo { numbers, boards }: &Bingo) -> Result<usize> {
+    let mut boards = boards.to_vec();
     let mut bingo_board: Option<(&Board, &usize)> = None;
 
-    for number in inputs {
+    for number in numbers {
         if bingo_board.is_some() {
             break;
         }
 
         for board in boards.iter_mut() {
-            let pos = board.pos(number);
-            match pos {
-                Some((row, col)) => board.mark(row, col),
-                None => (),
+            if let Some((row, col)) = board.pos(number) {
+                board.mark(row, col);
             }
         }
 
@@ -160,58 +182,53 @@ fn part_one(boards: &Vec<Board>, inputs: &Vec<usize>) -> Result<usize, Error> {
 
     match bingo_board {
         Some((b, num)) => Ok(b.sum_unmarked() * num),
-        None => Err(anyhow!("No answer for part_one")),
+        None => Err(PuzzleError::no_solution("No board got bingo")),
     }
 }
 
-fn part_two(boards: &Vec<Board>, inputs: &Vec<usize>) -> usize {
-    let mut copy_boards = boards.clone();
-    for number in inputs {
+pub fn part_two(Bingo { numbers, boards }: &Bingo) -> Result<usize> {
+    let mut copy_boards = boards.to_vec();
+    for number in numbers {
         for board in copy_boards.iter_mut() {
-            let pos = board.pos(number);
-            match pos {
-                Some((row, col)) => board.mark(row, col),
-                None => (),
+            if let Some((row, col)) = board.pos(number) {
+                board.mark(row, col);
             }
         }
 
         if copy_boards.len() > 1 {
-            copy_boards.retain(|b| b.has_bingo() == false);
+            copy_boards.retain(|b| !b.has_bingo());
         }
 
-        if copy_boards.len() == 1 {
-            if copy_boards[0].has_bingo() {
-                return copy_boards[0].sum_unmarked() * number;
-            }
+        if copy_boards.len() == 1 && copy_boards[0].has_bingo() {
+            return Ok(copy_boards[0].sum_unmarked() * number);
         }
     }
-    0
+    Err(PuzzleError::no_solution("Not every board got bingo"))
 }
 
-pub fn main(path: &Path) -> Result<(usize, Option<usize>)> {
-    let read_data = fs::read_to_string(path).expect("Failed to read");
-    let mut data = read_data.split("\n\n");
+pub struct Day4;
 
-    let inputs = data
-        .next()
-        .map(|x| {
-            x.split(',')
-                .map(|x| x.parse::<usize>().unwrap())
-                .collect::<Vec<usize>>()
-        })
-        .unwrap();
+impl Puzzle for Day4 {
+    type Input = Bingo;
+
+    const DAY: usize = 4;
+    const TITLE: &'static str = "Giant Squid";
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse(input)
+    }
 
-    let boards = data
-        .map(|str| -> Board { Board::new(&str.lines().collect()) })
-        .collect::<Vec<Board>>();
+    fn part_one(input: &Self::Input) -> Result<Answer> {
+        Ok(part_one(input)?.into())
+    }
 
-    Ok((
-        part_one(&boards, &inputs)?,
-        Some(part_two(&boards, &inputs)),
-    ))
+    fn part_two(input: &Self::Input) -> Result<Option<Answer>> {
+        Ok(Some(part_two(input)?.into()))
+    }
 }
 
 #[cfg(test)]
+#[allow(clippy::bool_assert_comparison, clippy::useless_vec)]
 mod tests {
     use super::*;
 
@@ -233,22 +250,9 @@ mod tests {
                         2  0 12  3  7";
     #[test]
     fn test_part_one() -> Result<()> {
-        let mut data = DATA.split("\n\n");
-
-        let inputs = data
-            .next()
-            .map(|x| {
-                x.split(',')
-                    .map(|x| x.parse::<usize>().unwrap())
-                    .collect::<Vec<usize>>()
-            })
-            .unwrap();
+        let bingo = parse(DATA)?;
 
-        let boards = data
-            .map(|str| -> Board { Board::new(&str.lines().collect()) })
-            .collect::<Vec<Board>>();
-
-        let v = part_one(&boards, &inputs)?;
+        let v = part_one(&bingo)?;
 
         assert_eq!(v, 4512);
         Ok(())
@@ -256,22 +260,9 @@ mod tests {
 
     #[test]
     fn test_part_two() -> Result<()> {
-        let mut data = DATA.split("\n\n");
-
-        let inputs = data
-            .next()
-            .map(|x| {
-                x.split(',')
-                    .map(|x| x.parse::<usize>().unwrap())
-                    .collect::<Vec<usize>>()
-            })
-            .unwrap();
-
-        let boards = data
-            .map(|str| -> Board { Board::new(&str.lines().collect()) })
-            .collect::<Vec<Board>>();
+        let bingo = parse(DATA)?;
 
-        let v = part_two(&boards, &inputs);
+        let v = part_two(&bingo)?;
 
         assert_eq!(v, 1924);
         Ok(())
@@ -280,13 +271,13 @@ mod tests {
     #[test]
     fn test_board_find_bingo_board() -> Result<()> {
         let board_rep1 = vec!["1 2 3", "4 5 6", "10 11 12"];
-        let mut board1 = Board::new(&board_rep1);
+        let mut board1 = Board::new(&board_rep1)?;
 
         board1.mark(0, 0);
         board1.mark(1, 0);
         board1.mark(2, 0);
 
-        let mut board2 = Board::new(&board_rep1);
+        let mut board2 = Board::new(&board_rep1)?;
         board2.mark(0, 0);
 
         assert_eq!(find_bingo_board(&vec![board1, board2]).is_some(), true);
@@ -296,7 +287,7 @@ mod tests {
     #[test]
     fn test_board_creation() -> Result<()> {
         let board_rep = vec!["1 2 3", "4 5 6", "10 11 12"];
-        let board = Board::new(&board_rep);
+        let board = Board::new(&board_rep)?;
         assert_eq!(board.to_string(), "(1, false)(2, false)(3, false)|(4, false)(5, false)(6, false)|(10, false)(11, false)(12, false)|");
         Ok(())
     }
@@ -304,7 +295,7 @@ mod tests {
     #[test]
     fn test_board_mark() -> Result<()> {
         let board_rep = vec!["1 2 3", "4 5 6", "10 11 12"];
-        let mut board = Board::new(&board_rep);
+        let mut board = Board::new(&board_rep)?;
         board.mark(0, 0);
         board.mark(0, 1);
 
@@ -315,7 +306,7 @@ mod tests {
     #[test]
     fn test_board_sum_unmarked() -> Result<()> {
         let board_rep = vec!["1 2 3", "4 5 6", "10 11 12"];
-        let mut board = Board::new(&board_rep);
+        let mut board = Board::new(&board_rep)?;
         board.mark(0, 0);
 
         assert_eq!(board.sum_unmarked(), 53);
@@ -325,7 +316,7 @@ mod tests {
     #[test]
     fn test_board_has_bingo_row() -> Result<()> {
         let board_rep = vec!["1 2 3", "4 5 6", "10 11 12"];
-        let mut board = Board::new(&board_rep);
+        let mut board = Board::new(&board_rep)?;
         board.mark(0, 0);
         board.mark(0, 1);
         board.mark(0, 2);
@@ -337,7 +328,7 @@ mod tests {
     #[test]
     fn test_board_has_bingo_col() -> Result<()> {
         let board_rep = vec!["1 2 3", "4 5 6", "10 11 12"];
-        let mut board = Board::new(&board_rep);
+        let mut board = Board::new(&board_rep)?;
         board.mark(0, 0);
         board.mark(1, 0);
         board.mark(2, 0);
@@ -349,7 +340,7 @@ mod tests {
     #[test]
     fn test_board_pos() -> Result<()> {
         let board_rep = vec!["1 2 3", "4 5 6", "10 11 12"];
-        let board = Board::new(&board_rep);
+        let board = Board::new(&board_rep)?;
 
         assert_eq!(board.pos(&11), Some((2, 1)));
         assert_eq!(board.pos(&4), Some((1, 0)));
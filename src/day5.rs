@@ -1,45 +1,23 @@
-use anyhow::{Error, Result};
+use crate::error::{PuzzleError, Result};
+use crate::grid::{Grid, Point};
+use crate::parse;
+use crate::point::{Point2, Vec2};
+use crate::render::{self, Image, Palette};
+use crate::{Answer, Puzzle};
 use std::collections::HashMap;
-use std::fs;
-use std::path::Path;
 use std::str::FromStr;
 
 #[derive(Debug, Copy, Clone, PartialEq, PartialOrd, Eq, Hash)]
-struct Point {
-    x: isize,
-    y: isize,
-}
-
-impl FromStr for Point {
-    type Err = Error;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut numbers = s.split(",");
-
-        let x = numbers.next().unwrap().parse::<isize>()?;
-        let y = numbers.next().unwrap().parse::<isize>()?;
-
-        Ok(Point { x, y })
-    }
-}
-
-#[derive(Debug, Copy, Clone, PartialEq, PartialOrd, Eq, Hash)]
-struct Vent {
-    start: Point,
-    end: Point,
+pub struct Vent {
+    start: Point2,
+    end: Point2,
 }
 
 impl FromStr for Vent {
-    type Err = Error;
+    type Err = PuzzleError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let points = s
-            .split(" -> ")
-            .map(|x: &str| Point::from_str(x).unwrap())
-            .collect::<Vec<Point>>();
-
-        let start = points[0];
-        let end = points[1];
+        let (start, end) = parse::rule(s)?;
 
         Ok(Vent { start, end })
     }
@@ -47,62 +25,43 @@ impl FromStr for Vent {
 
 impl Vent {
     fn is_acute_diagonal(&self) -> bool {
-        let x_diff = self.start.x - self.end.x;
-        let y_diff = self.start.y - self.end.y;
+        let diff = self.end - self.start;
 
-        x_diff.abs() == y_diff.abs()
+        diff.x.abs() == diff.y.abs()
     }
 
-    fn direction(&self) -> (isize, isize) {
-        let x_diff = self.end.x - self.start.x;
-        let y_diff = self.end.y - self.start.y;
-
-        (x_diff / x_diff.abs(), y_diff / y_diff.abs())
+    /// The step to take from the start to reach the end, which is only exact
+    /// for straight and acute diagonal vents
+    fn direction(&self) -> Vec2 {
+        (self.end - self.start).signum()
     }
 
-    fn covers(&self, diagonal: bool) -> Vec<Point> {
-        let is_y = self.start.y == self.end.y;
-        if is_y {
-            let range = if self.start.x < self.end.x {
-                self.start.x..=self.end.x
-            } else {
-                self.end.x..=self.start.x
-            };
+    /// The points the vent covers. Straight vents are walked from their
+    /// smaller end and diagonal ones from their start.
+    fn covers(&self, diagonal: bool) -> Vec<Point2> {
+        let diff = self.end - self.start;
 
-            return range
-                .map(|x: isize| -> Point { Point { x, y: self.start.y } })
-                .collect::<Vec<Point>>();
-        } else if self.start.x == self.end.x {
-            let range = if self.start.y < self.end.y {
-                self.start.y..=self.end.y
-            } else {
-                self.end.y..=self.start.y
-            };
-
-            return range
-                .map(|y: isize| -> Point { Point { y, x: self.start.x } })
-                .collect::<Vec<Point>>();
+        let vent = if diff.x == 0 || diff.y == 0 {
+            Vent {
+                start: self.start.min(self.end),
+                end: self.start.max(self.end),
+            }
         } else if self.is_acute_diagonal() && diagonal {
-            let (x_direction, y_direction) = self.direction();
-
-            let x_diff = self.end.x - self.start.x;
-
-            return (0..=x_diff.abs())
-                .map(|x: isize| -> Point {
-                    Point {
-                        x: self.start.x + x_direction * x,
-                        y: self.start.y + y_direction * x,
-                    }
-                })
-                .collect::<Vec<Point>>();
-        }
-
-        Vec::new()
+            *self
+        } else {
+            return Vec::new();
+        };
+
+        let direction = vent.direction();
+        (0..=diff.chebyshev() as isize)
+            .map(|i| vent.start + direction * i)
+            .collect::<Vec<Point2>>()
     }
 }
 
-fn calculate_n_overlaps(vents: &Vec<Vent>, diagonal: bool) -> usize {
-    let mut overlaps: HashMap<Point, isize> = HashMap::new();
+/// How many vents cover each point
+fn coverage(vents: &[Vent], diagonal: bool) -> HashMap<Point2, usize> {
+    let mut overlaps: HashMap<Point2, usize> = HashMap::new();
 
     for vent in vents.iter() {
         for point in vent.covers(diagonal) {
@@ -114,34 +73,71 @@ fn calculate_n_overlaps(vents: &Vec<Vent>, diagonal: bool) -> usize {
         }
     }
 
-    overlaps.values().filter(|x| *x > &1).count()
+    overlaps
 }
 
-fn part_one(input: &str) -> usize {
-    let vents: Vec<Vent> = input
-        .lines()
-        // TODO: How do I do question mark here instead?
-        .map(|line: &str| Vent::from_str(line).unwrap())
-        .collect();
-    calculate_n_overlaps(&vents, false)
+fn calculate_n_overlaps(vents: &[Vent], diagonal: bool) -> usize {
+    coverage(vents, diagonal)
+        .values()
+        .filter(|x| *x > &1)
+        .count()
 }
 
-fn part_two(input: &str) -> usize {
-    let vents: Vec<Vent> = input
-        .lines()
-        // TODO: How do I do question mark here instead?
-        .map(|line: &str| Vent::from_str(line).unwrap())
-        .collect();
-    calculate_n_overlaps(&vents, true)
+pub fn parse(input: &str) -> Result<Vec<Vent>> {
+    parse::each_line(input)
 }
 
-pub fn main(path: &Path) -> Result<(usize, Option<usize>)> {
-    let input = fs::read_to_string(path).expect("Failed to read");
+pub fn part_one(vents: &[Vent]) -> usize {
+    calculate_n_overlaps(vents, false)
+}
 
-    Ok((part_one(&input), Some(part_two(&input))))
+pub fn part_two(vents: &[Vent]) -> usize {
+    calculate_n_overlaps(vents, true)
+}
+
+/// A heat map of how many vents cover each point, diagonals included
+pub fn render(vents: &[Vent]) -> Option<Image> {
+    let coverage = coverage(vents, true)
+        .into_iter()
+        .filter_map(|(point, count)| Some((Point::try_from(point).ok()?, count)))
+        .collect::<Vec<(Point, usize)>>();
+    let width = coverage.iter().map(|((x, _), _)| x + 1).max()?;
+    let height = coverage.iter().map(|((_, y), _)| y + 1).max()?;
+
+    let mut grid = Grid::filled(width, height, 0);
+    for (point, count) in coverage {
+        grid[point] = count;
+    }
+    Some(render::colorize(&grid, Palette::Heat))
+}
+
+pub struct Day5;
+
+impl Puzzle for Day5 {
+    type Input = Vec<Vent>;
+
+    const DAY: usize = 5;
+    const TITLE: &'static str = "Hydrothermal Venture";
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse(input)
+    }
+
+    fn part_one(input: &Self::Input) -> Result<Answer> {
+        Ok(part_one(input).into())
+    }
+
+    fn part_two(input: &Self::Input) -> Result<Option<Answer>> {
+        Ok(Some(part_two(input).into()))
+    }
+
+    fn render(input: &Self::Input) -> Result<Option<Image>> {
+        Ok(render(input))
+    }
 }
 
 #[cfg(test)]
+#[allow(clippy::bool_assert_comparison)]
 mod tests {
     use super::*;
     const DATA: &str = "0,9 -> 5,9\n\
@@ -167,6 +163,27 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_parse_error() -> Result<()> {
+        let e = parse("0,9 -> 5,9\n8,0 -> 0,x8").unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            "line 2, column 10: invalid digit found in string"
+        );
+
+        let e = parse("0,9 -> 5,9\n8,0").unwrap_err();
+        assert!(matches!(
+            e,
+            PuzzleError::Parse {
+                line: 2,
+                column: 1,
+                ..
+            }
+        ));
+
+        Ok(())
+    }
+
     #[test]
     fn test_vent_diagonal_cover() -> Result<()> {
         let s = "1,0 -> 9,8";
@@ -185,10 +202,10 @@ mod tests {
         assert_eq!(
             vent.covers(true),
             vec![
-                Point { x: 1, y: 0 },
-                Point { x: 2, y: 1 },
-                Point { x: 3, y: 2 },
-                Point { x: 4, y: 3 },
+                Point2 { x: 1, y: 0 },
+                Point2 { x: 2, y: 1 },
+                Point2 { x: 3, y: 2 },
+                Point2 { x: 4, y: 3 },
             ]
         );
 
@@ -203,10 +220,10 @@ mod tests {
         assert_eq!(
             vent.covers(true),
             vec![
-                Point { x: 4, y: 3 },
-                Point { x: 3, y: 2 },
-                Point { x: 2, y: 1 },
-                Point { x: 1, y: 0 },
+                Point2 { x: 4, y: 3 },
+                Point2 { x: 3, y: 2 },
+                Point2 { x: 2, y: 1 },
+                Point2 { x: 1, y: 0 },
             ]
         );
 
@@ -221,10 +238,10 @@ mod tests {
         assert_eq!(
             vent.covers(true),
             vec![
-                Point { x: 1, y: 1 },
-                Point { x: 1, y: 2 },
-                Point { x: 1, y: 3 },
-                Point { x: 1, y: 4 }
+                Point2 { x: 1, y: 1 },
+                Point2 { x: 1, y: 2 },
+                Point2 { x: 1, y: 3 },
+                Point2 { x: 1, y: 4 }
             ]
         );
 
@@ -238,10 +255,10 @@ mod tests {
         assert_eq!(
             vent.covers(true),
             vec![
-                Point { x: 1, y: 1 },
-                Point { x: 1, y: 2 },
-                Point { x: 1, y: 3 },
-                Point { x: 1, y: 4 }
+                Point2 { x: 1, y: 1 },
+                Point2 { x: 1, y: 2 },
+                Point2 { x: 1, y: 3 },
+                Point2 { x: 1, y: 4 }
             ]
         );
 
@@ -256,10 +273,10 @@ mod tests {
         assert_eq!(
             vent.covers(true),
             vec![
-                Point { x: 1, y: 1 },
-                Point { x: 2, y: 1 },
-                Point { x: 3, y: 1 },
-                Point { x: 4, y: 1 }
+                Point2 { x: 1, y: 1 },
+                Point2 { x: 2, y: 1 },
+                Point2 { x: 3, y: 1 },
+                Point2 { x: 4, y: 1 }
             ]
         );
 
@@ -274,10 +291,10 @@ mod tests {
         assert_eq!(
             vent.covers(true),
             vec![
-                Point { x: 1, y: 1 },
-                Point { x: 2, y: 1 },
-                Point { x: 3, y: 1 },
-                Point { x: 4, y: 1 }
+                Point2 { x: 1, y: 1 },
+                Point2 { x: 2, y: 1 },
+                Point2 { x: 3, y: 1 },
+                Point2 { x: 4, y: 1 }
             ]
         );
 
@@ -1,19 +1,24 @@
-use anyhow::Result;
-use std::fs;
-use std::num::ParseIntError;
-use std::path::Path;
+use crate::error::{PuzzleError, Result};
+use crate::parse;
+use crate::{Answer, Puzzle};
 use std::str::FromStr;
 
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
-struct LanternFish {
+pub struct LanternFish {
     timer: usize,
 }
 
 impl FromStr for LanternFish {
-    type Err = ParseIntError;
+    type Err = PuzzleError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        s.parse::<usize>().map(|timer| LanternFish { timer })
+        let timer = s
+            .parse::<usize>()
+            .map_err(|e| PuzzleError::at(0, s, s, e))?;
+        if timer > 8 {
+            return Err(PuzzleError::at(0, s, s, "A timer can be at most 8"));
+        }
+        Ok(LanternFish { timer })
     }
 }
 
@@ -29,7 +34,7 @@ fn reproduce(mut fish_count: [usize; 9], ticks: usize) -> usize {
     fish_count.iter().sum()
 }
 
-fn get_fish_count(fishes: &Vec<LanternFish>) -> [usize; 9] {
+fn get_fish_count(fishes: &[LanternFish]) -> [usize; 9] {
     fishes
         .iter()
         .fold([0, 0, 0, 0, 0, 0, 0, 0, 0], |mut acc, f| {
@@ -38,15 +43,37 @@ fn get_fish_count(fishes: &Vec<LanternFish>) -> [usize; 9] {
         })
 }
 
-pub fn main(path: &Path) -> Result<(usize, Option<usize>)> {
-    let fishes = fs::read_to_string(path)?
-        .trim()
-        .split(",")
-        .map(LanternFish::from_str)
-        .collect::<Result<Vec<LanternFish>, ParseIntError>>()?;
+pub fn parse(input: &str) -> Result<Vec<LanternFish>> {
+    parse::comma_list(input.trim())
+}
+
+pub fn part_one(fishes: &[LanternFish]) -> usize {
+    reproduce(get_fish_count(fishes), 80)
+}
+
+pub fn part_two(fishes: &[LanternFish]) -> usize {
+    reproduce(get_fish_count(fishes), 256)
+}
+
+pub struct Day6;
+
+impl Puzzle for Day6 {
+    type Input = Vec<LanternFish>;
+
+    const DAY: usize = 6;
+    const TITLE: &'static str = "Lanternfish";
 
-    let fish_count = get_fish_count(&fishes);
-    Ok((reproduce(fish_count, 80), Some(reproduce(fish_count, 256))))
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse(input)
+    }
+
+    fn part_one(input: &Self::Input) -> Result<Answer> {
+        Ok(part_one(input).into())
+    }
+
+    fn part_two(input: &Self::Input) -> Result<Option<Answer>> {
+        Ok(Some(part_two(input).into()))
+    }
 }
 
 #[cfg(test)]
@@ -88,4 +115,11 @@ mod tests {
         assert_eq!(fish, LanternFish { timer: 3 });
         Ok(())
     }
+
+    #[test]
+    fn test_parse_error() -> Result<()> {
+        let e = parse("3,4,9,1").unwrap_err();
+        assert_eq!(e.to_string(), "line 1, column 5: A timer can be at most 8");
+        Ok(())
+    }
 }
@@ -1,26 +1,26 @@
-use anyhow::Result;
-use std::fs;
-use std::num::ParseIntError;
-use std::path::Path;
+use crate::error::Result;
+use crate::parse;
+use crate::{Answer, Puzzle};
 
 fn triangle_number(n: usize) -> usize {
-    (1..=n).fold(0, |sum, i| sum + i)
+    (1..=n).sum()
 }
 
 // TODO: Have this function return f32 instead?
-fn mean(values: &Vec<usize>) -> usize {
+fn mean(values: &[usize]) -> usize {
     let sum = values.iter().sum::<usize>();
     let n = values.len();
 
     sum / n
 }
 
-fn median(values: &mut Vec<usize>) -> usize {
+fn median(values: &[usize]) -> usize {
+    let mut values = values.to_vec();
     values.sort();
     values[values.len() / 2]
 }
 
-fn part_one(crabs: &mut Vec<usize>) -> usize {
+pub fn part_one(crabs: &[usize]) -> usize {
     let target_y = median(crabs);
 
     crabs
@@ -36,7 +36,7 @@ fn part_one(crabs: &mut Vec<usize>) -> usize {
         .sum::<usize>()
 }
 
-fn part_two(crabs: &mut Vec<usize>) -> usize {
+pub fn part_two(crabs: &[usize]) -> usize {
     let m = mean(crabs);
 
     let calc = |target: usize| {
@@ -65,18 +65,29 @@ fn part_two(crabs: &mut Vec<usize>) -> usize {
     }
 }
 
-pub fn main(path: &Path) -> Result<(usize, Option<usize>)> {
-    let input = fs::read_to_string(path).expect("Failed to read");
-    let data = input
-        .trim()
-        .split(",")
-        .map(|s| s.parse::<usize>())
-        .collect::<Result<Vec<usize>, ParseIntError>>()?;
-
-    Ok((
-        part_one(&mut data.clone()),
-        Some(part_two(&mut data.clone())),
-    ))
+pub fn parse(input: &str) -> Result<Vec<usize>> {
+    parse::comma_list(input.trim())
+}
+
+pub struct Day7;
+
+impl Puzzle for Day7 {
+    type Input = Vec<usize>;
+
+    const DAY: usize = 7;
+    const TITLE: &'static str = "The Treachery of Whales";
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse(input)
+    }
+
+    fn part_one(input: &Self::Input) -> Result<Answer> {
+        Ok(part_one(input).into())
+    }
+
+    fn part_two(input: &Self::Input) -> Result<Option<Answer>> {
+        Ok(Some(part_two(input).into()))
+    }
 }
 
 #[cfg(test)]
@@ -87,12 +98,12 @@ mod tests {
 
     #[test]
     fn test_part_one() -> Result<()> {
-        assert_eq!(part_one(&mut DATA.to_vec()), 37);
+        assert_eq!(part_one(&DATA), 37);
         Ok(())
     }
     #[test]
     fn test_part_two() -> Result<()> {
-        assert_eq!(part_two(&mut DATA.to_vec()), 168);
+        assert_eq!(part_two(&DATA), 168);
         Ok(())
     }
 }
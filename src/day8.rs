@@ -1,54 +1,78 @@
-use anyhow::{anyhow, Error, Result};
+use crate::error::{PuzzleError, Result};
+use crate::{parse, Answer, Puzzle};
 use std::collections::{HashMap, HashSet};
-use std::fs;
-use std::num::ParseIntError;
-use std::path::Path;
 use std::str::FromStr;
 
-pub fn main(path: &Path) -> Result<(usize, Option<usize>)> {
-    let content = fs::read_to_string(path)?;
-    Ok((part_one(&content)?, Some(part_two(&content)?)))
-}
-
 #[derive(Debug, PartialEq, Eq)]
-struct Input {
+pub struct Entry {
     patterns: [HashSet<char>; 10],
     output: [HashSet<char>; 4],
 }
 
-impl FromStr for Input {
-    type Err = Error;
+impl FromStr for Entry {
+    type Err = PuzzleError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (i, o) = s.split_once(" | ").unwrap_or(("", ""));
+        let (i, o) = s
+            .split_once(" | ")
+            .ok_or_else(|| PuzzleError::at(0, s, s, "Expected patterns | output"))?;
 
         let mut patterns: [HashSet<char>; 10] = Default::default();
         let mut output: [HashSet<char>; 4] = Default::default();
 
-        for (i, v) in i.split_whitespace().enumerate() {
-            patterns[i] = v.chars().collect::<HashSet<char>>();
-        }
+        read_sets(s, i, &mut patterns)?;
+        read_sets(s, o, &mut output)?;
 
-        for (i, v) in o.split_whitespace().enumerate() {
-            output[i] = v.chars().collect::<HashSet<char>>();
-        }
+        Ok(Entry { patterns, output })
+    }
+}
+
+/// Fill `sets` with the space separated segment sets in `part`, which is a
+/// slice of `line`
+fn read_sets(line: &str, part: &str, sets: &mut [HashSet<char>]) -> Result<()> {
+    let words = part.split_whitespace().collect::<Vec<_>>();
+    if words.len() != sets.len() {
+        return Err(PuzzleError::at(
+            0,
+            line,
+            part,
+            format!("Expected {} patterns, found {}", sets.len(), words.len()),
+        ));
+    }
 
-        Ok(Input { patterns, output })
+    for (set, word) in sets.iter_mut().zip(words) {
+        if let Some(c) = word.chars().find(|c| !('a'..='g').contains(c)) {
+            return Err(PuzzleError::at(
+                0,
+                line,
+                word,
+                format!("Unknown segment {:?}", c),
+            ));
+        }
+        *set = word.chars().collect::<HashSet<char>>();
     }
+
+    Ok(())
 }
 
-impl Input {
-    fn get_1(&self) -> &HashSet<char> {
-        self.patterns.iter().find(|p| p.len() == 2).unwrap()
+impl Entry {
+    fn get_with_len(&self, digit: usize, len: usize) -> Result<&HashSet<char>> {
+        self.patterns
+            .iter()
+            .find(|p| p.len() == len)
+            .ok_or_else(|| PuzzleError::no_solution(format!("No pattern for the digit {}", digit)))
+    }
+    fn get_1(&self) -> Result<&HashSet<char>> {
+        self.get_with_len(1, 2)
     }
-    fn get_4(&self) -> &HashSet<char> {
-        self.patterns.iter().find(|p| p.len() == 4).unwrap()
+    fn get_4(&self) -> Result<&HashSet<char>> {
+        self.get_with_len(4, 4)
     }
-    fn get_7(&self) -> &HashSet<char> {
-        self.patterns.iter().find(|p| p.len() == 3).unwrap()
+    fn get_7(&self) -> Result<&HashSet<char>> {
+        self.get_with_len(7, 3)
     }
-    fn get_8(&self) -> &HashSet<char> {
-        self.patterns.iter().find(|p| p.len() == 7).unwrap()
+    fn get_8(&self) -> Result<&HashSet<char>> {
+        self.get_with_len(8, 7)
     }
 }
 
@@ -66,10 +90,8 @@ fn match_digit(segments: [char; 7], set: &HashSet<char>) -> char {
     let v8 = HashSet::from([a, b, c, d, e, f, g]);
     let v9 = HashSet::from([a, b, c, d, f, g]);
 
-    let is_eq = |a: HashSet<_>, b: &HashSet<_>| -> bool {
-        let x = a.symmetric_difference(b).collect::<Vec<_>>();
-        x.len() == 0
-    };
+    let is_eq =
+        |a: HashSet<_>, b: &HashSet<_>| -> bool { a.symmetric_difference(b).next().is_none() };
 
     if is_eq(v0, set) {
         '0'
@@ -96,7 +118,15 @@ fn match_digit(segments: [char; 7], set: &HashSet<char>) -> char {
     }
 }
 
-fn decipher(input: &Input) -> Result<usize, ParseIntError> {
+/// The one segment in `set` that is not in `known`
+fn remaining(set: &HashSet<char>, known: HashSet<char>) -> Result<char> {
+    set.difference(&known)
+        .next()
+        .copied()
+        .ok_or_else(|| PuzzleError::no_solution("The patterns do not match any wiring"))
+}
+
+fn decipher(input: &Entry) -> Result<usize> {
     let segment_count = input.patterns.iter().fold(HashMap::new(), |mut map, set| {
         for c in set.iter() {
             let seg = map.entry(c).or_insert(0);
@@ -119,31 +149,12 @@ fn decipher(input: &Input) -> Result<usize, ParseIntError> {
             }
         });
 
-    let find_c_set = HashSet::from([b, e, f]);
-    let c = input
-        .get_1()
-        .difference(&find_c_set)
-        .collect::<Vec<&char>>()[0];
+    let c = remaining(input.get_1()?, HashSet::from([b, e, f]))?;
+    let a = remaining(input.get_7()?, HashSet::from([c, f]))?;
+    let d = remaining(input.get_4()?, HashSet::from([b, c, f]))?;
+    let g = remaining(input.get_8()?, HashSet::from([a, b, c, d, e, f]))?;
 
-    let find_a_set = HashSet::from([*c, f]);
-    let a = input
-        .get_7()
-        .difference(&find_a_set)
-        .collect::<Vec<&char>>()[0];
-
-    let find_d_set = HashSet::from([b, *c, f]);
-    let d = input
-        .get_4()
-        .difference(&find_d_set)
-        .collect::<Vec<&char>>()[0];
-
-    let find_g_set = HashSet::from([*a, b, *c, *d, e, f]);
-    let g = input
-        .get_8()
-        .difference(&find_g_set)
-        .collect::<Vec<&char>>()[0];
-
-    let segments: [char; 7] = [*a, b, *c, *d, e, f, *g];
+    let segments: [char; 7] = [a, b, c, d, e, f, g];
 
     input
         .output
@@ -151,39 +162,53 @@ fn decipher(input: &Input) -> Result<usize, ParseIntError> {
         .map(|set| -> char { match_digit(segments, set) })
         .collect::<String>()
         .parse::<usize>()
+        .map_err(PuzzleError::no_solution)
 }
 
-fn part_two(s: &str) -> Result<usize> {
-    let inputs = s
-        .trim()
-        .lines()
-        .map(Input::from_str)
-        .collect::<Result<Vec<Input>>>()?;
+pub fn parse(s: &str) -> Result<Vec<Entry>> {
+    parse::each_line(s)
+}
 
-    Ok(inputs
+pub fn part_two(entries: &[Entry]) -> Result<usize> {
+    Ok(entries
         .iter()
         .map(decipher)
-        .collect::<Result<Vec<usize>, ParseIntError>>()?
+        .collect::<Result<Vec<usize>>>()?
         .iter()
         .sum())
 }
 
-fn part_one(s: &str) -> Result<usize> {
-    let inputs = s
-        .trim()
-        .lines()
-        .map(Input::from_str)
-        .collect::<Result<Vec<Input>>>()?;
-
-    Ok(inputs
+pub fn part_one(entries: &[Entry]) -> usize {
+    entries
         .iter()
-        .flat_map(|input| {
-            input.output.iter().filter(|o| {
+        .flat_map(|entry| {
+            entry.output.iter().filter(|o| {
                 let len = o.len();
                 len == 2 || len == 4 || len == 3 || len == 7
             })
         })
-        .count())
+        .count()
+}
+
+pub struct Day8;
+
+impl Puzzle for Day8 {
+    type Input = Vec<Entry>;
+
+    const DAY: usize = 8;
+    const TITLE: &'static str = "Seven Segment Search";
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse(input)
+    }
+
+    fn part_one(input: &Self::Input) -> Result<Answer> {
+        Ok(part_one(input).into())
+    }
+
+    fn part_two(input: &Self::Input) -> Result<Option<Answer>> {
+        Ok(Some(part_two(input)?.into()))
+    }
 }
 
 #[cfg(test)]
@@ -205,7 +230,7 @@ mod tests {
 
     #[test]
     fn test_part_two() -> Result<()> {
-        let n = part_two(DATA)?;
+        let n = part_two(&parse(DATA)?)?;
 
         assert_eq!(n, 61229);
 
@@ -214,10 +239,25 @@ mod tests {
 
     #[test]
     fn test_part_one() -> Result<()> {
-        let n = part_one(DATA)?;
+        let n = part_one(&parse(DATA)?);
 
         assert_eq!(n, 26);
 
         Ok(())
     }
+
+    #[test]
+    fn test_parse_error() -> Result<()> {
+        let e = parse("\nbe cfbegad | fdgacbe cefdb cefbgd gcbe").unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            "line 2, column 1: Expected 10 patterns, found 2"
+        );
+
+        let data = DATA.replacen("cgeb", "cgxb", 1);
+        let e = parse(&data).unwrap_err();
+        assert_eq!(e.to_string(), "line 2, column 26: Unknown segment 'x'");
+
+        Ok(())
+    }
 }
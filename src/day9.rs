@@ -1,163 +1,88 @@
-use anyhow::{Error, Result};
+use crate::error::Result;
+use crate::grid::{Grid, Point};
+use crate::render::{self, Color, Image, Palette};
+use crate::{Answer, Puzzle};
 use std::collections::HashSet;
-use std::fs;
-use std::num::ParseIntError;
-use std::path::Path;
-use std::str::FromStr;
 
-struct Grid {
-    grid: Vec<Vec<usize>>,
-}
+/// The wall between basins, which is not part of any of them
+const WALL: usize = 9;
 
-impl FromStr for Grid {
-    type Err = Error;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let grid = s
-            .lines()
-            .map(|line| -> Vec<usize> {
-                line.chars()
-                    .map(|c| c.to_string().parse::<usize>())
-                    .collect::<Result<Vec<usize>, ParseIntError>>()
-                    .unwrap_or(Vec::new())
-            })
-            .collect::<Vec<Vec<usize>>>();
-
-        Ok(Grid { grid })
-    }
+fn is_low_point(grid: &Grid<usize>, point: Point) -> bool {
+    grid.neighbours(point).all(|n| grid[point] < grid[n])
 }
 
-fn subtract(a: usize, b: usize) -> Option<usize> {
-    if b > a {
-        None
-    } else {
-        Some(a - b)
+/// The size of the basin around a low point, which keeps going as long as
+/// the heights do not go down and stops at walls
+fn find_basin(grid: &Grid<usize>, low: Point) -> usize {
+    let mut queue = vec![low];
+    let mut basin = HashSet::new();
+
+    while let Some(point) = queue.pop() {
+        if grid[point] < WALL && basin.insert(point) {
+            queue.extend(
+                grid.neighbours(point)
+                    .filter(|n| grid[*n] >= grid[point] && grid[*n] != WALL),
+            );
+        }
     }
+
+    basin.len()
 }
 
-impl Grid {
-    fn iterate(&self) -> impl Iterator<Item = (&usize, (usize, usize))> {
-        self.grid
-            .iter()
-            .enumerate()
-            .flat_map(|(ri, r)| r.iter().enumerate().map(move |(ci, c)| (c, (ri, ci))))
-    }
+pub fn parse(data: &str) -> Result<Grid<usize>> {
+    Grid::digits(data, "a height")
+}
 
-    fn check_point(&self, (r, c): (usize, usize)) -> Option<usize> {
-        let row = self.grid.get(r);
-        let col = row.map(|r| r.get(c)).flatten().map(|x| *x);
+pub fn part_one(grid: &Grid<usize>) -> usize {
+    grid.iter()
+        .filter(|(point, _)| is_low_point(grid, *point))
+        .map(|(_, height)| height + 1)
+        .sum()
+}
 
-        col
-    }
+pub fn part_two(grid: &Grid<usize>) -> usize {
+    let mut basins: Vec<usize> = grid
+        .points()
+        .filter(|point| is_low_point(grid, *point))
+        .map(|point| find_basin(grid, point))
+        .collect::<Vec<usize>>();
 
-    fn neighbors(&self, (r, c): (usize, usize)) -> Vec<(usize, (usize, usize))> {
-        let up = subtract(r, 1)
-            .map(|v| self.check_point((v, c)))
-            .flatten()
-            .map(|v| (v, (r - 1, c)));
-        let right = self.check_point((r, c + 1)).map(|v| (v, (r, c + 1)));
-        let down = self.check_point((r + 1, c)).map(|v| (v, (r + 1, c)));
-
-        let left = subtract(c, 1)
-            .map(|v| self.check_point((r, v)))
-            .flatten()
-            .map(|v| (v, (r, c - 1)));
-
-        let neighbors = [up, right, down, left]
-            .iter()
-            .filter(|x| x.is_some())
-            .map(|x| x.unwrap())
-            .collect::<Vec<(usize, (usize, usize))>>();
-
-        neighbors
-    }
+    basins.sort_by(|a, b| b.cmp(a));
 
-    fn smaller_neighbors(&self, (r, c): (usize, usize)) -> Vec<(usize, (usize, usize))> {
-        let neighbors = self.neighbors((r, c));
-        let value = self.check_point((r, c));
+    basins.iter().take(3).product()
+}
 
-        let smallest = value
-            .map(move |v| {
-                neighbors
-                    .into_iter()
-                    .filter(|(n, _)| if *n == 9 { false } else { *n >= v })
-                    .collect::<Vec<(usize, (usize, usize))>>()
-            })
-            .unwrap_or(Vec::new());
+/// The height map with the low points in red
+pub fn render(grid: &Grid<usize>) -> Image {
+    let mut image = render::colorize(grid, Palette::Heights);
+    let low_points = grid.points().filter(|point| is_low_point(grid, *point));
+    render::overlay(&mut image, low_points, Color::RED);
+    image
+}
 
-        smallest
-    }
+pub struct Day9;
 
-    fn smaller_than_neighbors(&self, (r, c): (usize, usize)) -> bool {
-        let neighbors = self.neighbors((r, c));
-        let value = self.check_point((r, c));
+impl Puzzle for Day9 {
+    type Input = Grid<usize>;
 
-        let smallest = value
-            .map(|v| neighbors.iter().all(|(n, _)| v < *n))
-            .unwrap_or(false);
+    const DAY: usize = 9;
+    const TITLE: &'static str = "Smoke Basin";
 
-        smallest
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse(input)
     }
 
-    fn find_basin(&self, v: usize, (r, c): (usize, usize)) -> usize {
-        let wall = 9;
-
-        let mut queue = Vec::new();
-
-        queue.push((v, (r, c)));
-
-        let mut basin = HashSet::new();
-
-        while queue.len() > 0 {
-            let p = queue.pop();
-
-            match p {
-                Some((v, (r, c))) => {
-                    let contains = basin.contains(&(v, (r, c)));
-                    if v < wall && contains == false {
-                        basin.insert((v, (r, c)));
-                        for n in self.smaller_neighbors((r, c)) {
-                            queue.push(n);
-                        }
-                    } else {
-                        ()
-                    }
-                }
-                None => (),
-            }
-        }
-
-        basin.len()
+    fn part_one(input: &Self::Input) -> Result<Answer> {
+        Ok(part_one(input).into())
     }
-}
-
-fn part_one(data: &str) -> Result<usize> {
-    let grid = Grid::from_str(data)?;
-    let n: usize = grid
-        .iterate()
-        .filter(|(_, (r, c))| grid.smaller_than_neighbors((*r, *c)))
-        .map(|(v, _)| v + 1)
-        .sum();
-    Ok(n)
-}
-
-fn part_two(data: &str) -> Result<usize> {
-    let grid = Grid::from_str(data)?;
-    let mut basins: Vec<usize> = grid
-        .iterate()
-        .filter(|(_, (r, c))| grid.smaller_than_neighbors((*r, *c)))
-        .map(|(v, (r, c))| grid.find_basin(*v, (r, c)))
-        .collect::<Vec<usize>>();
-
-    basins.sort_by(|a, b| b.partial_cmp(a).unwrap());
-
-    Ok(basins.iter().take(3).product())
-}
 
-pub fn main(path: &Path) -> Result<(usize, Option<usize>)> {
-    let content = fs::read_to_string(path)?;
+    fn part_two(input: &Self::Input) -> Result<Option<Answer>> {
+        Ok(Some(part_two(input).into()))
+    }
 
-    Ok((part_one(&content)?, Some(part_two(&content)?)))
+    fn render(input: &Self::Input) -> Result<Option<Image>> {
+        Ok(Some(render(input)))
+    }
 }
 
 #[cfg(test)]
@@ -172,19 +97,28 @@ mod tests {
 
     #[test]
     fn test_part_two() -> Result<()> {
-        assert_eq!(part_two(DATA)?, 1134);
+        assert_eq!(part_two(&parse(DATA)?), 1134);
+        Ok(())
+    }
+
+    #[test]
+    fn test_render() -> Result<()> {
+        let image = render(&parse(DATA)?);
+        assert_eq!((image.width(), image.height()), (10, 5));
+        assert_eq!(image.iter().filter(|(_, c)| **c == Color::RED).count(), 4);
+        assert_eq!(image[(1, 0)], Color::RED);
         Ok(())
     }
 
     #[test]
     fn test_part_one() -> Result<()> {
-        assert_eq!(part_one(DATA)?, 15);
+        assert_eq!(part_one(&parse(DATA)?), 15);
         Ok(())
     }
 
     #[test]
     fn test_parse() -> Result<()> {
-        let grid = Grid::from_str(DATA)?;
+        let grid = parse(DATA)?;
 
         let g: Vec<Vec<usize>> = vec![
             vec![2, 1, 9, 9, 9, 4, 3, 2, 1, 0],
@@ -194,7 +128,20 @@ mod tests {
             vec![9, 8, 9, 9, 9, 6, 5, 6, 7, 8],
         ];
 
-        assert_eq!(grid.grid, g);
+        assert_eq!(grid.rows().map(<[usize]>::to_vec).collect::<Vec<_>>(), g);
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_error() -> Result<()> {
+        let e = parse("2199\n39x7").unwrap_err();
+        assert_eq!(e.to_string(), "line 2, column 3: 'x' is not a height");
+
+        let e = parse("2199\n398").unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            "line 2, column 1: Rows have different lengths"
+        );
         Ok(())
     }
 }
@@ -0,0 +1,112 @@
+use std::io;
+use thiserror::Error;
+
+pub type Result<T, E = PuzzleError> = std::result::Result<T, E>;
+
+#[derive(Debug, Error)]
+pub enum PuzzleError {
+    #[error(transparent)]
+    Io(#[from] io::Error),
+
+    /// Lines and columns are 1-based
+    #[error("line {line}, column {column}: {message}")]
+    Parse {
+        line: usize,
+        column: usize,
+        message: String,
+    },
+
+    #[error("invalid input: {0}")]
+    InvalidInput(String),
+
+    #[error("no solution: {0}")]
+    NoSolution(String),
+}
+
+impl PuzzleError {
+    pub fn parse(line: usize, column: usize, message: impl ToString) -> Self {
+        PuzzleError::Parse {
+            line,
+            column,
+            message: message.to_string(),
+        }
+    }
+
+    /// Parse error for `token`, which must be a slice of `line`, on the
+    /// zero-based `index`th line of the input
+    pub fn at(index: usize, line: &str, token: &str, message: impl ToString) -> Self {
+        PuzzleError::parse(index + 1, column_of(line, token), message)
+    }
+
+    pub fn invalid(message: impl ToString) -> Self {
+        PuzzleError::InvalidInput(message.to_string())
+    }
+
+    pub fn no_solution(message: impl ToString) -> Self {
+        PuzzleError::NoSolution(message.to_string())
+    }
+
+    /// Move a parse error in a substring to where the substring starts in
+    /// the full input. Positions on the first line of the substring are
+    /// offset by the starting column, while later lines keep their column.
+    pub fn shift(self, line: usize, column: usize) -> Self {
+        match self {
+            PuzzleError::Parse {
+                line: l,
+                column: c,
+                message,
+            } => PuzzleError::Parse {
+                line: line + l - 1,
+                column: if l == 1 { column + c - 1 } else { c },
+                message,
+            },
+            e => e,
+        }
+    }
+}
+
+/// The 1-based column where `token` starts within `line`
+pub fn column_of(line: &str, token: &str) -> usize {
+    let start = line.as_ptr() as usize;
+    let offset = (token.as_ptr() as usize).wrapping_sub(start);
+
+    if offset <= line.len() {
+        line[..offset].chars().count() + 1
+    } else {
+        line.find(token)
+            .map(|i| line[..i].chars().count() + 1)
+            .unwrap_or(1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_at() -> Result<()> {
+        let line = "12 -> ab";
+        let token = line.split(' ').nth(2).unwrap();
+
+        assert_eq!(
+            PuzzleError::at(4, line, token, "bad number").to_string(),
+            "line 5, column 7: bad number"
+        );
+        assert_eq!(column_of(line, "ab"), 7);
+        assert_eq!(column_of(line, "xy"), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_shift() -> Result<()> {
+        let e = PuzzleError::parse(1, 3, "oops").shift(10, 5);
+        assert_eq!(e.to_string(), "line 10, column 7: oops");
+
+        let e = PuzzleError::parse(2, 3, "oops").shift(10, 5);
+        assert_eq!(e.to_string(), "line 11, column 3: oops");
+
+        let e = PuzzleError::invalid("empty").shift(10, 5);
+        assert_eq!(e.to_string(), "invalid input: empty");
+        Ok(())
+    }
+}
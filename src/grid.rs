@@ -0,0 +1,303 @@
+use crate::error::{PuzzleError, Result};
+use std::fmt;
+use std::ops::{Index, IndexMut};
+
+/// A position in a grid as `(x, y)`, where `(0, 0)` is the top left
+pub type Point = (usize, usize);
+
+/// A dense rectangular grid, stored row by row. A grid without cells is
+/// always 0×0, so a grid is never wide but empty or tall but empty.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// A grid of `width` columns from `cells` given row by row, or `None`
+    /// if they don't fill the last row
+    pub fn new(width: usize, cells: Vec<T>) -> Option<Grid<T>> {
+        if width == 0 || !cells.len().is_multiple_of(width) {
+            return None;
+        }
+        let height = cells.len() / width;
+        Some(Grid::sized(width, height, cells))
+    }
+
+    /// A `width` by `height` grid of `cells`, which is 0×0 when there are none
+    fn sized(width: usize, height: usize, cells: Vec<T>) -> Grid<T> {
+        if cells.is_empty() {
+            return Grid {
+                width: 0,
+                height: 0,
+                cells,
+            };
+        }
+        Grid {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    /// Read a grid from lines of characters, turning each into a cell with
+    /// `cell`. Errors name the character as not being `what`, like "a height".
+    /// Empty lines are skipped.
+    pub fn parse(s: &str, what: &str, cell: impl Fn(char) -> Option<T>) -> Result<Grid<T>> {
+        let mut cells = Vec::new();
+        let mut width = None;
+
+        let rows = s
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty());
+
+        for (i, line) in rows {
+            if *width.get_or_insert(line.chars().count()) != line.chars().count() {
+                return Err(PuzzleError::parse(i + 1, 1, "Rows have different lengths"));
+            }
+
+            for (x, c) in line.chars().enumerate() {
+                cells.push(cell(c).ok_or_else(|| {
+                    PuzzleError::parse(i + 1, x + 1, format!("{:?} is not {}", c, what))
+                })?);
+            }
+        }
+
+        width
+            .and_then(|width| Grid::new(width, cells))
+            .ok_or_else(|| PuzzleError::invalid("The grid is empty"))
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, (x, y): Point) -> Option<&T> {
+        if x < self.width && y < self.height {
+            self.cells.get(y * self.width + x)
+        } else {
+            None
+        }
+    }
+
+    pub fn get_mut(&mut self, (x, y): Point) -> Option<&mut T> {
+        if x < self.width && y < self.height {
+            self.cells.get_mut(y * self.width + x)
+        } else {
+            None
+        }
+    }
+
+    /// Every point of the grid, row by row
+    pub fn points(&self) -> impl Iterator<Item = Point> {
+        let width = self.width;
+        (0..self.cells.len()).map(move |i| (i % width, i / width))
+    }
+
+    /// Every point of the grid with its cell, row by row
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> {
+        self.points().zip(&self.cells)
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        // An empty grid is 0 wide, but it has no cells to chunk anyway
+        self.cells.chunks(self.width.max(1))
+    }
+
+    /// The cells of column `x` from top to bottom, or none if `x` is
+    /// outside the grid
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        let cells = if x < self.width { &self.cells[..] } else { &[] };
+        cells.iter().skip(x).step_by(self.width.max(1))
+    }
+
+    fn offsets(
+        &self,
+        (x, y): Point,
+        offsets: &'static [(isize, isize)],
+    ) -> impl Iterator<Item = Point> {
+        let (width, height) = (self.width, self.height);
+        offsets.iter().filter_map(move |(dx, dy)| {
+            let x = x.checked_add_signed(*dx).filter(|x| *x < width)?;
+            let y = y.checked_add_signed(*dy).filter(|y| *y < height)?;
+            Some((x, y))
+        })
+    }
+
+    /// The points up, right, down and left of `point` that are in the grid
+    pub fn neighbours(&self, point: Point) -> impl Iterator<Item = Point> {
+        self.offsets(point, &[(0, -1), (1, 0), (0, 1), (-1, 0)])
+    }
+
+    /// The points next to `point`, diagonals included, that are in the grid
+    pub fn neighbours8(&self, point: Point) -> impl Iterator<Item = Point> {
+        self.offsets(
+            point,
+            &[
+                (-1, -1),
+                (0, -1),
+                (1, -1),
+                (1, 0),
+                (1, 1),
+                (0, 1),
+                (-1, 1),
+                (-1, 0),
+            ],
+        )
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+
+    /// Repeat the grid `times_x` times to the right and `times_y` times down,
+    /// where `tile` turns a cell into its copy in the tile at `(x, y)`
+    pub fn tile(&self, times_x: usize, times_y: usize, tile: impl Fn(&T, Point) -> T) -> Grid<T> {
+        let width = self.width * times_x;
+        let height = self.height * times_y;
+
+        let cells = (0..width * height)
+            .map(|i| {
+                let (x, y) = (i % width, i / width);
+                let cell = &self[(x % self.width, y % self.height)];
+                tile(cell, (x / self.width, y / self.height))
+            })
+            .collect();
+
+        Grid::sized(width, height, cells)
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    /// A grid where every cell is `value`
+    pub fn filled(width: usize, height: usize, value: T) -> Grid<T> {
+        Grid::sized(width, height, vec![value; width * height])
+    }
+
+    /// The grid repeated `times_x` times to the right and `times_y` times
+    /// down
+    pub fn repeat(&self, times_x: usize, times_y: usize) -> Grid<T> {
+        self.tile(times_x, times_y, |cell, _| cell.clone())
+    }
+}
+
+impl Grid<usize> {
+    /// Read a grid of single digits, like a height map
+    pub fn digits(s: &str, what: &str) -> Result<Grid<usize>> {
+        Grid::parse(s, what, |c| c.to_digit(10).map(|d| d as usize))
+    }
+}
+
+impl<T> Index<Point> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, point: Point) -> &T {
+        self.get(point)
+            .unwrap_or_else(|| panic!("{:?} is outside the grid", point))
+    }
+}
+
+impl<T> IndexMut<Point> for Grid<T> {
+    fn index_mut(&mut self, point: Point) -> &mut T {
+        self.get_mut(point)
+            .unwrap_or_else(|| panic!("{:?} is outside the grid", point))
+    }
+}
+
+/// Prints every row of cells on its own line, without separators
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for row in self.rows() {
+            for cell in row {
+                write!(f, "{}", cell)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() -> Result<()> {
+        let grid = Grid::digits("\n123\n456\n", "a digit")?;
+
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[(2, 1)], 6);
+        assert_eq!(grid.get((3, 0)), None);
+        assert_eq!(grid.column(1).collect::<Vec<_>>(), vec![&2, &5]);
+        assert_eq!(grid.column(4).count(), 0);
+        assert_eq!(grid.to_string(), "123\n456\n");
+        Ok(())
+    }
+
+    #[test]
+    fn test_neighbours() -> Result<()> {
+        let grid = Grid::filled(3, 3, 0);
+
+        assert_eq!(
+            grid.neighbours((0, 1)).collect::<Vec<_>>(),
+            vec![(0, 0), (1, 1), (0, 2)]
+        );
+        assert_eq!(grid.neighbours8((1, 1)).count(), 8);
+        assert_eq!(grid.neighbours8((2, 2)).count(), 3);
+        Ok(())
+    }
+
+    #[test]
+    fn test_tile() -> Result<()> {
+        let grid = Grid::digits("12", "a digit")?;
+
+        assert_eq!(grid.repeat(2, 2).to_string(), "1212\n1212\n");
+        assert_eq!(
+            grid.tile(2, 2, |d, (x, y)| d + x + 2 * y).to_string(),
+            "1223\n3445\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_empty() -> Result<()> {
+        let grids = [
+            Grid::filled(0, 3, 1),
+            Grid::filled(3, 0, 1),
+            Grid::digits("12", "a digit")?.repeat(0, 2),
+            Grid::new(3, Vec::new()).unwrap(),
+        ];
+
+        for grid in grids {
+            assert_eq!((grid.width(), grid.height()), (0, 0));
+            assert_eq!(grid.rows().count(), 0);
+            assert_eq!(grid.column(0).count(), 0);
+            assert_eq!(grid.points().count(), 0);
+            assert_eq!(grid.to_string(), "");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_error() -> Result<()> {
+        let e = Grid::digits("12\n3x", "a digit").unwrap_err();
+        assert_eq!(e.to_string(), "line 2, column 2: 'x' is not a digit");
+
+        let e = Grid::digits("12\n345", "a digit").unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            "line 2, column 1: Rows have different lengths"
+        );
+        Ok(())
+    }
+}
@@ -1,3 +1,5 @@
+pub mod answer;
+pub mod bench;
 pub mod day1;
 pub mod day10;
 pub mod day11;
@@ -5,7 +7,17 @@ pub mod day12;
 pub mod day13;
 pub mod day14;
 pub mod day15;
+pub mod day16;
+pub mod day17;
+pub mod day18;
+pub mod day19;
 pub mod day2;
+pub mod day20;
+pub mod day21;
+pub mod day22;
+pub mod day23;
+pub mod day24;
+pub mod day25;
 pub mod day3;
 pub mod day4;
 pub mod day5;
@@ -13,3 +25,53 @@ pub mod day6;
 pub mod day7;
 pub mod day8;
 pub mod day9;
+pub mod error;
+pub mod grid;
+pub mod manifest;
+pub mod parse;
+pub mod point;
+pub mod render;
+pub mod runner;
+pub mod search;
+pub mod solver;
+// Fixtures shared by the unit and integration tests, not part of the API
+#[doc(hidden)]
+pub mod testing;
+
+pub use answer::Answer;
+pub use error::PuzzleError;
+pub use manifest::Manifest;
+pub use solver::{Parsed, Part, Puzzle, Solution, Solver};
+
+/// Every implemented day, ordered by day number
+pub static SOLVERS: &[&dyn Solver] = &[
+    &day1::Day1,
+    &day2::Day2,
+    &day3::Day3,
+    &day4::Day4,
+    &day5::Day5,
+    &day6::Day6,
+    &day7::Day7,
+    &day8::Day8,
+    &day9::Day9,
+    &day10::Day10,
+    &day11::Day11,
+    &day12::Day12,
+    &day13::Day13,
+    &day14::Day14,
+    &day15::Day15,
+    &day16::Day16,
+    &day17::Day17,
+    &day18::Day18,
+    &day19::Day19,
+    &day20::Day20,
+    &day21::Day21,
+    &day22::Day22,
+    &day23::Day23,
+    &day24::Day24,
+    &day25::Day25,
+];
+
+pub fn solver(day: usize) -> Option<&'static dyn Solver> {
+    SOLVERS.iter().find(|solver| solver.day() == day).copied()
+}
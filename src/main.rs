@@ -1,18 +1,438 @@
-use advent_of_code_2021::{
-    day1, day10, day11, day12, day13, day14, day15, day2, day3, day4, day5, day6, day7, day8, day9,
-};
-use anyhow::{anyhow, Result};
-use std::path::Path;
-
-fn pad_newlines(answer: String) -> String {
-    answer.lines().collect::<Vec<_>>().join("\n   ")
+use advent_of_code_2021::render::Format;
+use advent_of_code_2021::runner::{self, Input, Run};
+use advent_of_code_2021::{bench, manifest};
+use advent_of_code_2021::{solver, Answer, Manifest, Part, Solver};
+use anyhow::{anyhow, Context, Result};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+fn print_answer(label: &str, answer: &Answer) {
+    match answer {
+        Answer::Grid(rows) => println!("{}: {}", label, rows.join("\n   ")),
+        _ => println!("{}: {}", label, answer),
+    }
+}
+
+fn summarize(answer: &Answer) -> String {
+    match answer {
+        Answer::Grid(rows) => format!("<{} row grid>", rows.len()),
+        _ => answer.to_string(),
+    }
+}
+
+fn format_duration(duration: Duration) -> String {
+    format!("{:.2?}", duration)
+}
+
+fn row(day: usize, run: &Result<Run>) -> Vec<String> {
+    let day = day.to_string();
+    match run {
+        Ok(run) => vec![
+            day,
+            format_duration(run.parse),
+            match &run.a.result {
+                Ok(a) => summarize(a),
+                Err(_) => String::from("-"),
+            },
+            format_duration(run.a.elapsed),
+            match &run.b.result {
+                Ok(Some(b)) => summarize(b),
+                _ => String::from("-"),
+            },
+            format_duration(run.b.elapsed),
+            match run.error() {
+                Some(e) => format!("error: {:#}", e),
+                None => String::from("ok"),
+            },
+        ],
+        Err(e) => status_row(day, format!("error: {:#}", e)),
+    }
+}
+
+/// A row for a day that has no answers, only a status
+fn status_row(day: String, status: String) -> Vec<String> {
+    let mut row = vec![day];
+    row.extend(["-", "-", "-", "-", "-"].map(String::from));
+    row.push(status);
+    row
+}
+
+fn print_table(header: &[&str], rows: &[Vec<String>]) {
+    let mut widths = header.iter().map(|h| h.len()).collect::<Vec<_>>();
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let print_row = |row: &[String]| {
+        let cells = row
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+            .collect::<Vec<_>>();
+        println!("{}", cells.join(" | ").trim_end());
+    };
+
+    print_row(&header.iter().map(|h| h.to_string()).collect::<Vec<_>>());
+    println!(
+        "{}",
+        widths
+            .iter()
+            .map(|width| "-".repeat(*width))
+            .collect::<Vec<_>>()
+            .join("-+-")
+    );
+    for row in rows {
+        print_row(row);
+    }
 }
 
-fn as_result<A: ToString, B: ToString>(value: (A, Option<B>)) -> (String, Option<String>) {
-    (
-        value.0.to_string(),
-        value.1.map(|answer| answer.to_string()),
-    )
+/// Split several days into those to run and those skipped for not having a
+/// default input, with the status to show for each skipped day. A single day
+/// is never skipped, so asking for it reports the missing input.
+fn skip_missing<'a>(solvers: &[&'a dyn Solver]) -> (Vec<&'a dyn Solver>, Vec<(usize, String)>) {
+    let mut ready = Vec::new();
+    let mut skipped = Vec::new();
+
+    for solver in solvers {
+        let path = solver.default_input();
+        if solvers.len() > 1 && !path.exists() {
+            skipped.push((solver.day(), format!("skipped: no {}", path.display())));
+        } else {
+            ready.push(*solver);
+        }
+    }
+    (ready, skipped)
+}
+
+fn run_many(solvers: &[&dyn Solver], args: &[String]) -> Result<()> {
+    let jobs = match args {
+        [] => runner::default_jobs(),
+        [flag, value] if flag == "--jobs" => match value.parse() {
+            Ok(jobs) if jobs > 0 => jobs,
+            _ => return Err(anyhow!("--jobs needs a number of at least 1")),
+        },
+        _ => {
+            return Err(anyhow!(
+                "Only --jobs can be given when running several days"
+            ))
+        }
+    };
+
+    let (ready, skipped) = skip_missing(solvers);
+
+    let runs = ready
+        .iter()
+        .map(|solver| solver.day())
+        .zip(runner::run_parallel(&ready, jobs))
+        .collect::<Vec<_>>();
+
+    let mut rows = runs
+        .iter()
+        .map(|(day, run)| (*day, row(*day, run)))
+        .chain(
+            skipped
+                .into_iter()
+                .map(|(day, status)| (day, status_row(day.to_string(), status))),
+        )
+        .collect::<Vec<_>>();
+    rows.sort_by_key(|(day, _)| *day);
+    print_table(
+        &[
+            "Day", "Parse", "Part A", "Time A", "Part B", "Time B", "Status",
+        ],
+        &rows.into_iter().map(|(_, row)| row).collect::<Vec<_>>(),
+    );
+
+    let failed = runs
+        .iter()
+        .filter(|(_, run)| !matches!(run, Ok(run) if run.error().is_none()))
+        .count();
+    if failed > 0 {
+        return Err(anyhow!("{} of {} days failed", failed, runs.len()));
+    }
+
+    Ok(())
+}
+
+fn bench_rows(solver: &dyn Solver, warmup: usize, runs: usize, parts: &[Part]) -> Vec<Vec<String>> {
+    let day = solver.day().to_string();
+    let error_row = |phase: &str, e: anyhow::Error| {
+        let mut row = vec![day.clone(), phase.to_string()];
+        row.extend(["-", "-", "-", "-"].map(String::from));
+        row.push(format!("error: {:#}", e));
+        row
+    };
+
+    let path = solver.default_input();
+    let input = match runner::read_input(&path) {
+        Ok(input) => input,
+        Err(e) => return vec![error_row("input", e)],
+    };
+
+    let parse = bench::measure(warmup, runs, || Ok(solver.parse(&input)?));
+    let parsed = match solver.parse(&input) {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            let e = anyhow::Error::from(e).context(format!("Failed to parse {}", path.display()));
+            return vec![error_row("parse", e)];
+        }
+    };
+
+    let mut phases = vec![(String::from("parse"), parse)];
+    for &part in parts {
+        let stats = bench::measure(warmup, runs, || Ok(parsed.part(part)?));
+        phases.push((format!("part {}", part), stats));
+    }
+
+    phases
+        .into_iter()
+        .map(|(phase, stats)| match stats {
+            Ok(stats) => vec![
+                day.clone(),
+                phase,
+                format_duration(stats.min),
+                format_duration(stats.median),
+                format_duration(stats.mean),
+                format_duration(stats.p95),
+                String::from("ok"),
+            ],
+            Err(e) => error_row(&phase, e),
+        })
+        .collect()
+}
+
+fn run_bench(args: &[String]) -> Result<()> {
+    let mut spec = None;
+    let mut warmup = 1;
+    let mut runs = 10;
+    let mut parts = vec![Part::A, Part::B];
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--part" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| anyhow!("{} needs a value", arg))?;
+                parts = runner::parts(value)?;
+            }
+            "--runs" | "--warmup" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| anyhow!("{} needs a value", arg))?
+                    .parse()
+                    .map_err(|_| anyhow!("{} needs a number", arg))?;
+                if arg == "--runs" {
+                    runs = value;
+                } else {
+                    warmup = value;
+                }
+            }
+            _ if spec.is_none() => spec = Some(arg),
+            _ => return Err(anyhow!("Unexpected argument {:?}", arg)),
+        }
+    }
+
+    let spec = spec.ok_or_else(|| anyhow!("Missing day to benchmark"))?;
+    if runs == 0 {
+        return Err(anyhow!("--runs must be at least 1"));
+    }
+
+    let (solvers, skipped) = skip_missing(&runner::select(spec)?);
+
+    println!("{} runs after {} warm-up runs", runs, warmup);
+    let mut rows = solvers
+        .into_iter()
+        .flat_map(|solver| {
+            bench_rows(solver, warmup, runs, &parts)
+                .into_iter()
+                .map(move |row| (solver.day(), row))
+        })
+        .chain(skipped.into_iter().map(|(day, status)| {
+            let mut row = vec![day.to_string(), String::from("input")];
+            row.extend(["-", "-", "-", "-"].map(String::from));
+            row.push(status);
+            (day, row)
+        }))
+        .collect::<Vec<_>>();
+    // Sorting is stable, so the phases of a day stay in order
+    rows.sort_by_key(|(day, _)| *day);
+    let rows = rows.into_iter().map(|(_, row)| row).collect::<Vec<_>>();
+
+    print_table(
+        &["Day", "Phase", "Min", "Median", "Mean", "P95", "Status"],
+        &rows,
+    );
+
+    let failed = rows
+        .iter()
+        .filter(|row| row[6].starts_with("error"))
+        .count();
+    if failed > 0 {
+        return Err(anyhow!("{} benchmarks failed", failed));
+    }
+
+    Ok(())
+}
+
+fn run_verify(args: &[String]) -> Result<()> {
+    let path = Path::new(manifest::DEFAULT_PATH);
+    let manifest =
+        Manifest::read(path).with_context(|| format!("Failed to read {}", path.display()))?;
+
+    // Without a spec, only the days that have expected answers are checked
+    let solvers = match args {
+        [] => manifest.days().into_iter().filter_map(solver).collect(),
+        [spec] => runner::select(spec)?,
+        _ => return Err(anyhow!("Expected at most one day to verify")),
+    };
+
+    let (solvers, skipped) = skip_missing(&solvers);
+
+    let mut rows = Vec::new();
+    let mut diffs = Vec::new();
+    let mut failed = 0;
+    for (day, status) in skipped {
+        for part in [Part::A, Part::B] {
+            rows.push((day, vec![day.to_string(), part.to_string(), status.clone()]));
+        }
+    }
+
+    let runs = runner::run_parallel(&solvers, runner::default_jobs());
+    for (solver, run) in solvers.iter().zip(runs) {
+        let day = solver.day();
+
+        for part in [Part::A, Part::B] {
+            let expected = manifest.expected(day, part);
+            let actual = match &run {
+                Ok(run) => run.answer(part),
+                Err(e) => Err(e),
+            };
+
+            let status = match (expected, actual) {
+                (_, Err(e)) => format!("error: {:#}", e),
+                (None, Ok(_)) => String::from("unchecked"),
+                (Some(_), Ok(None)) => String::from("missing"),
+                (Some(expected), Ok(Some(actual))) if expected == actual => String::from("ok"),
+                (Some(expected), Ok(Some(actual))) => {
+                    diffs.push((day, part, manifest::diff(expected, actual)));
+                    String::from("wrong")
+                }
+            };
+
+            if status != "ok" && status != "unchecked" {
+                failed += 1;
+            }
+            rows.push((day, vec![day.to_string(), part.to_string(), status]));
+        }
+    }
+
+    rows.sort_by_key(|(day, _)| *day);
+    let rows = rows.into_iter().map(|(_, row)| row).collect::<Vec<_>>();
+    print_table(&["Day", "Part", "Status"], &rows);
+    for (day, part, lines) in diffs {
+        println!();
+        println!("Day {} part {}:", day, part);
+        for line in lines {
+            println!("  {}", line);
+        }
+    }
+
+    if failed > 0 {
+        return Err(anyhow!("{} of {} answers failed", failed, rows.len()));
+    }
+
+    Ok(())
+}
+
+fn run_render(args: &[String]) -> Result<()> {
+    let mut spec = None;
+    let mut input = None;
+    let mut format = Format::Svg;
+    let mut output = None;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or_else(|| anyhow!("{} needs a value", arg));
+        match arg.as_str() {
+            "--format" => format = value()?.parse()?,
+            "--output" => output = Some(PathBuf::from(value()?)),
+            "--input-text" => input = Some(Input::Text(value()?.clone())),
+            _ if spec.is_none() => spec = Some(arg),
+            _ if input.is_none() => input = Some(Input::from_arg(arg)),
+            _ => return Err(anyhow!("Unexpected argument {:?}", arg)),
+        }
+    }
+
+    let spec = spec.ok_or_else(|| anyhow!("Missing day to render"))?;
+    let solver = match runner::select(spec)?[..] {
+        [solver] => solver,
+        _ => return Err(anyhow!("Only one day can be rendered at a time")),
+    };
+
+    let input = input.unwrap_or_else(|| Input::File(solver.default_input()));
+    let image = solver
+        .parse(&input.read()?)
+        .and_then(|parsed| parsed.render())
+        .with_context(|| format!("Failed to render {}", input))?
+        .ok_or_else(|| anyhow!("Day {} has nothing to render", solver.day()))?;
+
+    let path = output
+        .unwrap_or_else(|| PathBuf::from(format!("day{}.{}", solver.day(), format.extension())));
+    let file =
+        File::create(&path).with_context(|| format!("Failed to create {}", path.display()))?;
+    let mut out = BufWriter::new(file);
+    format
+        .write(&image, &mut out)
+        .and_then(|_| out.flush())
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+
+    println!(
+        "Wrote a {}x{} image to {}",
+        image.width(),
+        image.height(),
+        path.display()
+    );
+    Ok(())
+}
+
+fn run_day(solver: &dyn Solver, args: &[String]) -> Result<()> {
+    let mut input = None;
+    let mut parts = vec![Part::A, Part::B];
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or_else(|| anyhow!("{} needs a value", arg));
+        let next = match arg.as_str() {
+            "--part" => {
+                parts = runner::parts(value()?)?;
+                continue;
+            }
+            "--input-text" => Input::Text(value()?.clone()),
+            flag if flag.starts_with("--") => {
+                return Err(anyhow!(
+                    "Unknown option {:?}, a single day only takes --part and --input-text",
+                    flag
+                ))
+            }
+            _ => Input::from_arg(arg),
+        };
+
+        if input.replace(next).is_some() {
+            return Err(anyhow!("Expected at most one input"));
+        }
+    }
+
+    let input = input.unwrap_or_else(|| Input::File(solver.default_input()));
+    for (part, answer) in input.solve(solver, &parts)? {
+        print_answer(&part.to_string(), &answer);
+    }
+
+    Ok(())
 }
 
 fn main() -> Result<()> {
@@ -22,68 +442,28 @@ fn main() -> Result<()> {
         return Err(anyhow!("Not enough arguments"));
     }
 
-    let path: Option<&Path> = if args.len() == 3 {
-        Some(Path::new(&args[2]))
-    } else {
-        None
-    };
+    if args[1] == "bench" {
+        return run_bench(&args[2..]);
+    }
 
-    #[allow(overlapping_range_endpoints, unreachable_patterns)]
-    let result: (String, Option<String>) = match args[1].parse() {
-        Ok(1) => as_result(day1::main(
-            path.unwrap_or_else(|| Path::new("data/day1.txt")),
-        )?),
-        Ok(2) => as_result(day2::main(
-            path.unwrap_or_else(|| Path::new("data/day2.txt")),
-        )?),
-        Ok(3) => as_result(day3::main(
-            path.unwrap_or_else(|| Path::new("data/day3.txt")),
-        )?),
-        Ok(4) => as_result(day4::main(
-            path.unwrap_or_else(|| Path::new("data/day4.txt")),
-        )?),
-        Ok(5) => as_result(day5::main(
-            path.unwrap_or_else(|| Path::new("data/day5.txt")),
-        )?),
-        Ok(6) => as_result(day6::main(
-            path.unwrap_or_else(|| Path::new("data/day6.txt")),
-        )?),
-        Ok(7) => as_result(day7::main(
-            path.unwrap_or_else(|| Path::new("data/day7.txt")),
-        )?),
-        Ok(8) => as_result(day8::main(
-            path.unwrap_or_else(|| Path::new("data/day8.txt")),
-        )?),
-        Ok(9) => as_result(day9::main(
-            path.unwrap_or_else(|| Path::new("data/day9.txt")),
-        )?),
-        Ok(10) => as_result(day10::main(
-            path.unwrap_or_else(|| Path::new("data/day10.txt")),
-        )?),
-        Ok(11) => as_result(day11::main(
-            path.unwrap_or_else(|| Path::new("data/day11.txt")),
-        )?),
-        Ok(12) => as_result(day12::main(
-            path.unwrap_or_else(|| Path::new("data/day12.txt")),
-        )?),
-        Ok(13) => as_result(day13::main(
-            path.unwrap_or_else(|| Path::new("data/day13.txt")),
-        )?),
-        Ok(14) => as_result(day14::main(
-            path.unwrap_or_else(|| Path::new("data/day14.txt")),
-        )?),
-        Ok(15) => as_result(day15::main(
-            path.unwrap_or_else(|| Path::new("data/day15.txt")),
-        )?),
-        Ok(1..=25) => return Err(anyhow!("No implementation for this day yet")),
-        Ok(day) => return Err(anyhow!("Day {} is not a valid day for advent of code", day)),
-        Err(_) => return Err(anyhow!("{:?} is not a valid day", args[1])),
-    };
+    if args[1] == "verify" {
+        return run_verify(&args[2..]);
+    }
 
-    println!("A: {}", pad_newlines(result.0));
-    if let Some(b) = result.1 {
-        println!("B: {}", pad_newlines(b));
+    if args[1] == "render" {
+        return run_render(&args[2..]);
     }
 
-    Ok(())
+    if let Some((spec, flags)) = runner::positional_days(&args[1..]) {
+        return run_many(&runner::select(&spec)?, flags);
+    }
+
+    let solvers = runner::select(&args[1])?;
+
+    // A single day prints its full answers, while several days get a summary
+    if args[1].parse::<usize>().is_err() {
+        return run_many(&solvers, &args[2..]);
+    }
+
+    run_day(solvers[0], &args[2..])
 }
@@ -0,0 +1,157 @@
+use crate::error::{column_of, PuzzleError, Result};
+use crate::{Answer, Part};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+/// Where the expected answers for the inputs in `data` are kept
+pub const DEFAULT_PATH: &str = "data/answers";
+
+/// Expected answers, read from lines of `<day> <part> <answer>` where the
+/// answer is written the way `Answer::serialize` writes it. Empty lines and
+/// lines starting with `#` are skipped.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Manifest {
+    answers: BTreeMap<(usize, Part), Answer>,
+}
+
+impl Manifest {
+    pub fn read(path: &Path) -> Result<Manifest> {
+        fs::read_to_string(path)?.parse()
+    }
+
+    pub fn expected(&self, day: usize, part: Part) -> Option<&Answer> {
+        self.answers.get(&(day, part))
+    }
+
+    /// Every day with at least one expected answer, in order
+    pub fn days(&self) -> Vec<usize> {
+        let mut days = self.answers.keys().map(|(day, _)| *day).collect::<Vec<_>>();
+        days.dedup();
+        days
+    }
+}
+
+impl FromStr for Manifest {
+    type Err = PuzzleError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut answers = BTreeMap::new();
+
+        for (i, line) in s.lines().enumerate() {
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let mut fields = line.splitn(3, ' ');
+            let (day, part, answer) = match (fields.next(), fields.next(), fields.next()) {
+                (Some(day), Some(part), Some(answer)) => (day, part, answer),
+                _ => {
+                    return Err(PuzzleError::at(
+                        i,
+                        line,
+                        line,
+                        "Expected <day> <part> <answer>",
+                    ))
+                }
+            };
+
+            let day = day
+                .parse::<usize>()
+                .map_err(|e| PuzzleError::at(i, line, day, e))?;
+            let part = part
+                .parse::<Part>()
+                .map_err(|_| PuzzleError::at(i, line, part, "Expected part a or b"))?;
+            let answer =
+                Answer::deserialize(answer).map_err(|e| e.shift(i + 1, column_of(line, answer)))?;
+
+            if answers.insert((day, part), answer).is_some() {
+                return Err(PuzzleError::at(
+                    i,
+                    line,
+                    line,
+                    format!("Day {} part {} is listed twice", day, part),
+                ));
+            }
+        }
+
+        Ok(Manifest { answers })
+    }
+}
+
+/// Lines showing how `actual` differs from `expected`, prefixed by `-` for
+/// expected and `+` for actual lines. Lines that match are prefixed by a
+/// space, so a grid shows which of its rows are wrong.
+pub fn diff(expected: &Answer, actual: &Answer) -> Vec<String> {
+    let expected = expected.to_string();
+    let actual = actual.to_string();
+    let expected = expected.lines().collect::<Vec<_>>();
+    let actual = actual.lines().collect::<Vec<_>>();
+
+    let mut lines = Vec::new();
+    for i in 0..expected.len().max(actual.len()) {
+        match (expected.get(i), actual.get(i)) {
+            (Some(e), Some(a)) if e == a => lines.push(format!("  {}", e)),
+            (e, a) => {
+                lines.extend(e.map(|e| format!("- {}", e)));
+                lines.extend(a.map(|a| format!("+ {}", a)));
+            }
+        }
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DATA: &str = "# day part answer\n\
+                        \n\
+                        1 a int:1448\n\
+                        1 b int:1471\n\
+                        13 b grid:#_\\n_#";
+
+    #[test]
+    fn test_parse() -> Result<()> {
+        let manifest = Manifest::from_str(DATA)?;
+
+        assert_eq!(manifest.expected(1, Part::A), Some(&Answer::Integer(1448)));
+        assert_eq!(
+            manifest.expected(13, Part::B),
+            Some(&Answer::grid("#_\n_#"))
+        );
+        assert_eq!(manifest.expected(13, Part::A), None);
+        assert_eq!(manifest.days(), vec![1, 13]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_error() -> Result<()> {
+        let e = Manifest::from_str("1 a int:1\n2 c int:2").unwrap_err();
+        assert_eq!(e.to_string(), "line 2, column 3: Expected part a or b");
+
+        let e = Manifest::from_str("1 a int:1\n1 a int:2").unwrap_err();
+        assert!(matches!(e, PuzzleError::Parse { line: 2, .. }));
+
+        let e = Manifest::from_str("1 a int:1\n2 a text:a\\b").unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            "line 2, column 11: Invalid escape sequence \\b"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_diff() -> Result<()> {
+        assert_eq!(
+            diff(&Answer::Integer(1), &Answer::Integer(2)),
+            vec!["- 1", "+ 2"]
+        );
+        assert_eq!(
+            diff(&Answer::grid("#_\n_#"), &Answer::grid("#_\n##\n__")),
+            vec!["  #_", "- _#", "+ ##", "+ __"]
+        );
+        Ok(())
+    }
+}
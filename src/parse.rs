@@ -0,0 +1,212 @@
+//! Helpers for reading puzzle input that keep the 1-based line and column of
+//! whatever fails to parse. Grids of digits are read with `Grid::digits`.
+
+use crate::error::{column_of, PuzzleError, Result};
+use std::char::ParseCharError;
+use std::convert::Infallible;
+use std::num::ParseIntError;
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+
+/// An error from parsing a single token, which can be placed in the line the
+/// token was cut from
+pub trait TokenError {
+    fn within(self, line: &str, token: &str) -> PuzzleError;
+}
+
+/// Errors that already know their position inside the token
+impl TokenError for PuzzleError {
+    fn within(self, line: &str, token: &str) -> PuzzleError {
+        self.shift(1, column_of(line, token))
+    }
+}
+
+impl TokenError for ParseIntError {
+    fn within(self, line: &str, token: &str) -> PuzzleError {
+        PuzzleError::at(0, line, token, self)
+    }
+}
+
+impl TokenError for ParseCharError {
+    fn within(self, line: &str, token: &str) -> PuzzleError {
+        PuzzleError::at(0, line, token, self)
+    }
+}
+
+impl TokenError for Infallible {
+    fn within(self, _: &str, _: &str) -> PuzzleError {
+        match self {}
+    }
+}
+
+/// Parse `token`, which must be a slice of `line`, with errors pointing at
+/// where it is on the line
+pub fn token<T>(line: &str, token: &str) -> Result<T>
+where
+    T: FromStr,
+    T::Err: TokenError,
+{
+    token.parse().map_err(|e: T::Err| e.within(line, token))
+}
+
+/// The lines that aren't blank, with their 1-based line numbers
+pub fn lines(input: &str) -> impl Iterator<Item = (usize, &str)> {
+    input
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| (i + 1, line))
+}
+
+/// Parse every line that isn't blank, with errors moved to their line
+pub fn each_line<T>(input: &str) -> Result<Vec<T>>
+where
+    T: FromStr,
+    T::Err: TokenError,
+{
+    lines(input)
+        .map(|(n, line)| token(line, line).map_err(|e| e.shift(n, 1)))
+        .collect()
+}
+
+/// Parse a line of values separated by commas, like `3,4,3,1,2`
+pub fn comma_list<T>(line: &str) -> Result<Vec<T>>
+where
+    T: FromStr,
+    T::Err: TokenError,
+{
+    line.split(',').map(|value| token(line, value)).collect()
+}
+
+/// Split a rule like `a -> b` into its two sides
+pub fn arrow(line: &str) -> Result<(&str, &str)> {
+    line.split_once(" -> ")
+        .ok_or_else(|| PuzzleError::at(0, line, line, "Expected a rule like a -> b"))
+}
+
+/// Parse both sides of a rule like `a -> b`
+pub fn rule<A, B>(line: &str) -> Result<(A, B)>
+where
+    A: FromStr,
+    A::Err: TokenError,
+    B: FromStr,
+    B::Err: TokenError,
+{
+    let (a, b) = arrow(line)?;
+    Ok((token(line, a)?, token(line, b)?))
+}
+
+/// Parse a range like `x=20..30` for the given `axis`, where `text` is a
+/// slice of `line`
+pub fn range(line: &str, text: &str, axis: &str) -> Result<RangeInclusive<isize>> {
+    let bounds = text
+        .strip_prefix(axis)
+        .and_then(|r| r.strip_prefix('='))
+        .ok_or_else(|| PuzzleError::at(0, line, text, format!("Expected {}=", axis)))?;
+    let (start, end) = bounds
+        .split_once("..")
+        .ok_or_else(|| PuzzleError::at(0, line, bounds, "Expected a range like 1..2"))?;
+
+    let (start, end) = (
+        token::<isize>(line, start.trim())?,
+        token::<isize>(line, end.trim())?,
+    );
+    if start > end {
+        return Err(PuzzleError::at(0, line, bounds, "The range is empty"));
+    }
+
+    Ok(start..=end)
+}
+
+/// Split the input into sections separated by blank lines, with the 1-based
+/// line each section starts on. Errors in a section can be moved to the
+/// full input with `shift(line, 1)`.
+pub fn sections(input: &str) -> Vec<(usize, &str)> {
+    let mut sections = Vec::new();
+    let mut start = None;
+    let mut offset = 0;
+
+    for (i, line) in input.split_inclusive('\n').enumerate() {
+        if line.trim().is_empty() {
+            if let Some((n, from)) = start.take() {
+                sections.push((n, &input[from..offset]));
+            }
+        } else if start.is_none() {
+            start = Some((i + 1, offset));
+        }
+        offset += line.len();
+    }
+    if let Some((n, from)) = start {
+        sections.push((n, &input[from..]));
+    }
+
+    sections
+        .into_iter()
+        .map(|(n, section)| (n, section.trim_end_matches(['\n', '\r'])))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_comma_list() -> Result<()> {
+        assert_eq!(comma_list::<usize>("3,4,3")?, vec![3, 4, 3]);
+
+        let e = comma_list::<usize>("3,4,x3").unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            "line 1, column 5: invalid digit found in string"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_rule() -> Result<()> {
+        assert_eq!(rule::<String, char>("CH -> B")?, ("CH".to_string(), 'B'));
+
+        let e = rule::<String, char>("CH -> BB").unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            "line 1, column 7: too many characters in string"
+        );
+
+        let e = rule::<String, char>("CH - B").unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            "line 1, column 1: Expected a rule like a -> b"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_range() -> Result<()> {
+        let line = "x=20..30, y=-10..-5";
+        assert_eq!(range(line, &line[..8], "x")?, 20..=30);
+        assert_eq!(range(line, &line[10..], "y")?, -10..=-5);
+
+        let e = range(line, &line[10..], "z").unwrap_err();
+        assert_eq!(e.to_string(), "line 1, column 11: Expected z=");
+
+        let e = range("x=5..1", "x=5..1", "x").unwrap_err();
+        assert_eq!(e.to_string(), "line 1, column 3: The range is empty");
+        Ok(())
+    }
+
+    #[test]
+    fn test_sections() -> Result<()> {
+        let input = "7,4,9\n\n22 13\n 8  2\n\n\n3 15\n";
+        assert_eq!(
+            sections(input),
+            vec![(1, "7,4,9"), (3, "22 13\n 8  2"), (7, "3 15")]
+        );
+
+        let e = each_line::<usize>("1\n\n3\nx").unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            "line 4, column 1: invalid digit found in string"
+        );
+        Ok(())
+    }
+}
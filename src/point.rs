@@ -0,0 +1,187 @@
+use crate::error::{PuzzleError, Result};
+use std::convert::TryFrom;
+use std::num::TryFromIntError;
+use std::ops::{Add, Mul, Neg, Sub};
+use std::str::FromStr;
+
+/// A position on a plane
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Point2 {
+    pub x: isize,
+    pub y: isize,
+}
+
+/// The difference between two positions on a plane
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Vec2 {
+    pub x: isize,
+    pub y: isize,
+}
+
+impl Point2 {
+    pub fn new(x: isize, y: isize) -> Point2 {
+        Point2 { x, y }
+    }
+
+    pub fn manhattan(&self, other: &Point2) -> usize {
+        (*self - *other).manhattan()
+    }
+
+    /// The distance when diagonal steps count as one, like a king in chess
+    pub fn chebyshev(&self, other: &Point2) -> usize {
+        (*self - *other).chebyshev()
+    }
+}
+
+impl Vec2 {
+    pub fn new(x: isize, y: isize) -> Vec2 {
+        Vec2 { x, y }
+    }
+
+    pub fn manhattan(&self) -> usize {
+        self.x.unsigned_abs() + self.y.unsigned_abs()
+    }
+
+    pub fn chebyshev(&self) -> usize {
+        self.x.unsigned_abs().max(self.y.unsigned_abs())
+    }
+
+    /// A step of at most one in each direction towards where the vector
+    /// points, so a diagonal vector of any length gives a diagonal step
+    pub fn signum(&self) -> Vec2 {
+        Vec2::new(self.x.signum(), self.y.signum())
+    }
+
+    pub fn dot(&self, other: &Vec2) -> isize {
+        self.x * other.x + self.y * other.y
+    }
+}
+
+impl Add<Vec2> for Point2 {
+    type Output = Point2;
+
+    fn add(self, v: Vec2) -> Point2 {
+        Point2::new(self.x + v.x, self.y + v.y)
+    }
+}
+
+impl Sub<Vec2> for Point2 {
+    type Output = Point2;
+
+    fn sub(self, v: Vec2) -> Point2 {
+        Point2::new(self.x - v.x, self.y - v.y)
+    }
+}
+
+impl Sub for Point2 {
+    type Output = Vec2;
+
+    fn sub(self, other: Point2) -> Vec2 {
+        Vec2::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl Add for Vec2 {
+    type Output = Vec2;
+
+    fn add(self, other: Vec2) -> Vec2 {
+        Vec2::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl Sub for Vec2 {
+    type Output = Vec2;
+
+    fn sub(self, other: Vec2) -> Vec2 {
+        Vec2::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl Neg for Vec2 {
+    type Output = Vec2;
+
+    fn neg(self) -> Vec2 {
+        Vec2::new(-self.x, -self.y)
+    }
+}
+
+impl Mul<isize> for Vec2 {
+    type Output = Vec2;
+
+    fn mul(self, n: isize) -> Vec2 {
+        Vec2::new(self.x * n, self.y * n)
+    }
+}
+
+/// Grid positions are `(x, y)` with unsigned coordinates
+impl TryFrom<(usize, usize)> for Point2 {
+    type Error = TryFromIntError;
+
+    fn try_from((x, y): (usize, usize)) -> Result<Point2, TryFromIntError> {
+        Ok(Point2::new(isize::try_from(x)?, isize::try_from(y)?))
+    }
+}
+
+impl TryFrom<Point2> for (usize, usize) {
+    type Error = TryFromIntError;
+
+    fn try_from(point: Point2) -> Result<(usize, usize), TryFromIntError> {
+        Ok((usize::try_from(point.x)?, usize::try_from(point.y)?))
+    }
+}
+
+/// Parses points like `1,2`
+impl FromStr for Point2 {
+    type Err = PuzzleError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (x, y) = s
+            .split_once(',')
+            .ok_or_else(|| PuzzleError::at(0, s, s, "Expected a point like 1,2"))?;
+
+        let number = |n: &str| n.parse::<isize>().map_err(|e| PuzzleError::at(0, s, n, e));
+
+        Ok(Point2 {
+            x: number(x)?,
+            y: number(y)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_arithmetic() -> Result<()> {
+        let a = Point2::new(1, 2);
+        let b = Point2::new(4, -2);
+
+        assert_eq!(b - a, Vec2::new(3, -4));
+        assert_eq!(a + (b - a) * 2, Point2::new(7, -6));
+        assert_eq!(a - -(b - a).signum(), Point2::new(2, 1));
+        assert_eq!(a.manhattan(&b), 7);
+        assert_eq!(a.chebyshev(&b), 4);
+        Ok(())
+    }
+
+    #[test]
+    fn test_unsigned() -> Result<()> {
+        assert_eq!(Point2::try_from((3, 4)), Ok(Point2::new(3, 4)));
+        assert_eq!(<(usize, usize)>::try_from(Point2::new(3, 4)), Ok((3, 4)));
+        assert!(<(usize, usize)>::try_from(Point2::new(-1, 4)).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_error() -> Result<()> {
+        assert_eq!(Point2::from_str("-3,12")?, Point2::new(-3, 12));
+
+        let e = Point2::from_str("3,1x").unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            "line 1, column 3: invalid digit found in string"
+        );
+        Ok(())
+    }
+}
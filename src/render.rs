@@ -0,0 +1,271 @@
+use crate::error::{PuzzleError, Result};
+use crate::grid::{Grid, Point};
+use crate::point::Point2;
+use std::io::{self, Write};
+use std::str::FromStr;
+
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Color {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+impl Color {
+    pub const BLACK: Color = Color::new(0, 0, 0);
+    pub const WHITE: Color = Color::new(255, 255, 255);
+    pub const RED: Color = Color::new(220, 30, 30);
+
+    pub const fn new(r: u8, g: u8, b: u8) -> Color {
+        Color { r, g, b }
+    }
+
+    /// How bright the color looks, used when writing grayscale images
+    pub fn luma(&self) -> u8 {
+        ((299 * self.r as usize + 587 * self.g as usize + 114 * self.b as usize) / 1000) as u8
+    }
+
+    /// The color `num / den` of the way from this color to `other`
+    fn mix(&self, other: &Color, num: usize, den: usize) -> Color {
+        let channel = |a: u8, b: u8| ((a as usize * (den - num) + b as usize * num) / den) as u8;
+        Color::new(
+            channel(self.r, other.r),
+            channel(self.g, other.g),
+            channel(self.b, other.b),
+        )
+    }
+
+    fn hex(&self) -> String {
+        format!("#{:02x}{:02x}{:02x}", self.r, self.g, self.b)
+    }
+}
+
+/// Ways of turning numbers into colors, from the lowest value to the highest
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Palette {
+    /// Black to white
+    Gray,
+    /// Deep water, through lowlands and mountains, to snow on the peaks
+    Heights,
+    /// Safe green, through yellow, to dangerous red
+    Risks,
+    /// Black, through red, to yellow where there is the most
+    Heat,
+}
+
+impl Palette {
+    fn stops(&self) -> &'static [Color] {
+        const GRAY: &[Color] = &[Color::BLACK, Color::WHITE];
+        const HEIGHTS: &[Color] = &[
+            Color::new(10, 30, 110),
+            Color::new(40, 140, 60),
+            Color::new(130, 90, 50),
+            Color::WHITE,
+        ];
+        const RISKS: &[Color] = &[
+            Color::new(30, 150, 60),
+            Color::new(240, 220, 40),
+            Color::new(200, 20, 20),
+        ];
+        const HEAT: &[Color] = &[
+            Color::BLACK,
+            Color::new(200, 20, 20),
+            Color::new(255, 230, 60),
+        ];
+
+        match self {
+            Palette::Gray => GRAY,
+            Palette::Heights => HEIGHTS,
+            Palette::Risks => RISKS,
+            Palette::Heat => HEAT,
+        }
+    }
+
+    /// The color of `value` on a scale from `min` to `max`
+    pub fn color(&self, value: usize, min: usize, max: usize) -> Color {
+        let stops = self.stops();
+        if max <= min {
+            return stops[0];
+        }
+
+        // Find the pair of stops the value falls between, and how far along
+        // it is, without going through floats
+        let segments = stops.len() - 1;
+        let (along, den) = ((value.clamp(min, max) - min) * segments, max - min);
+        let i = (along / den).min(segments - 1);
+        stops[i].mix(&stops[i + 1], along - i * den, den)
+    }
+}
+
+/// A picture with one pixel per cell
+pub type Image = Grid<Color>;
+
+/// Color every cell of the grid, scaled from its lowest to its highest value
+pub fn colorize(grid: &Grid<usize>, palette: Palette) -> Image {
+    let min = grid.iter().map(|(_, v)| *v).min().unwrap_or_default();
+    let max = grid.iter().map(|(_, v)| *v).max().unwrap_or_default();
+    grid.map(|v| palette.color(*v, min, max))
+}
+
+/// Draw the points on a background large enough to fit every point and the
+/// origin, or `None` if there is nothing to draw. Points with negative
+/// coordinates are left out.
+pub fn points<'a>(
+    points: impl IntoIterator<Item = &'a Point2>,
+    color: Color,
+    background: Color,
+) -> Option<Image> {
+    let points = points
+        .into_iter()
+        .filter_map(|point| Point::try_from(*point).ok())
+        .collect::<Vec<Point>>();
+    let width = points.iter().map(|(x, _)| x + 1).max()?;
+    let height = points.iter().map(|(_, y)| y + 1).max()?;
+
+    let mut image = Grid::filled(width, height, background);
+    overlay(&mut image, points, color);
+    Some(image)
+}
+
+/// Paint cells on top of an image, like a path or cells to highlight.
+/// Cells outside the image are skipped.
+pub fn overlay(image: &mut Image, cells: impl IntoIterator<Item = Point>, color: Color) {
+    for cell in cells {
+        if let Some(pixel) = image.get_mut(cell) {
+            *pixel = color;
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Format {
+    /// Binary grayscale netpbm image
+    Pgm,
+    /// Binary color netpbm image
+    Ppm,
+    Svg,
+}
+
+impl Format {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Format::Pgm => "pgm",
+            Format::Ppm => "ppm",
+            Format::Svg => "svg",
+        }
+    }
+
+    pub fn write(&self, image: &Image, out: &mut dyn Write) -> io::Result<()> {
+        match self {
+            Format::Pgm => write_pgm(image, out),
+            Format::Ppm => write_ppm(image, out),
+            Format::Svg => write_svg(image, out),
+        }
+    }
+}
+
+impl FromStr for Format {
+    type Err = PuzzleError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "pgm" => Ok(Format::Pgm),
+            "ppm" => Ok(Format::Ppm),
+            "svg" => Ok(Format::Svg),
+            _ => Err(PuzzleError::invalid(format!(
+                "{:?} is not pgm, ppm or svg",
+                s
+            ))),
+        }
+    }
+}
+
+pub fn write_pgm(image: &Image, out: &mut dyn Write) -> io::Result<()> {
+    write!(out, "P5\n{} {}\n255\n", image.width(), image.height())?;
+    let pixels = image.iter().map(|(_, c)| c.luma()).collect::<Vec<u8>>();
+    out.write_all(&pixels)
+}
+
+pub fn write_ppm(image: &Image, out: &mut dyn Write) -> io::Result<()> {
+    write!(out, "P6\n{} {}\n255\n", image.width(), image.height())?;
+    let pixels = image
+        .iter()
+        .flat_map(|(_, c)| [c.r, c.g, c.b])
+        .collect::<Vec<u8>>();
+    out.write_all(&pixels)
+}
+
+/// Every cell is a square of 10 by 10 units. Runs of cells with the same
+/// color in a row are drawn as one rectangle to keep the file small.
+pub fn write_svg(image: &Image, out: &mut dyn Write) -> io::Result<()> {
+    const CELL: usize = 10;
+
+    writeln!(
+        out,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}" shape-rendering="crispEdges">"#,
+        w = image.width() * CELL,
+        h = image.height() * CELL,
+    )?;
+
+    for (y, row) in image.rows().enumerate() {
+        let mut x = 0;
+        for run in row.chunk_by(|a, b| a == b) {
+            writeln!(
+                out,
+                r#"<rect x="{}" y="{}" width="{}" height="{}" fill="{}"/>"#,
+                x * CELL,
+                y * CELL,
+                run.len() * CELL,
+                CELL,
+                run[0].hex(),
+            )?;
+            x += run.len();
+        }
+    }
+
+    writeln!(out, "</svg>")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_palette() -> Result<()> {
+        assert_eq!(Palette::Gray.color(0, 0, 10), Color::BLACK);
+        assert_eq!(Palette::Gray.color(5, 0, 10), Color::new(127, 127, 127));
+        assert_eq!(Palette::Gray.color(20, 0, 10), Color::WHITE);
+        assert_eq!(Palette::Heights.color(9, 0, 9), Color::WHITE);
+        assert_eq!(Palette::Heat.color(3, 3, 3), Color::BLACK);
+        Ok(())
+    }
+
+    #[test]
+    fn test_netpbm() -> Result<()> {
+        let mut image = colorize(&Grid::digits("09", "a digit")?, Palette::Gray);
+        overlay(&mut image, [(0, 0), (5, 5)], Color::RED);
+
+        let mut pgm = Vec::new();
+        write_pgm(&image, &mut pgm)?;
+        assert_eq!(pgm, b"P5\n2 1\n255\n\x56\xff");
+
+        let mut ppm = Vec::new();
+        write_ppm(&image, &mut ppm)?;
+        assert_eq!(ppm, b"P6\n2 1\n255\n\xdc\x1e\x1e\xff\xff\xff");
+        Ok(())
+    }
+
+    #[test]
+    fn test_svg() -> Result<()> {
+        let dots = [Point2::new(0, 1), Point2::new(1, 1), Point2::new(-1, 0)];
+        let image = points(&dots, Color::WHITE, Color::BLACK).unwrap();
+        assert_eq!((image.width(), image.height()), (2, 2));
+
+        let mut svg = Vec::new();
+        Format::Svg.write(&image, &mut svg)?;
+        let svg = String::from_utf8(svg).unwrap();
+        assert_eq!(svg.matches("<rect").count(), 2);
+        assert!(svg.contains(r##"<rect x="0" y="10" width="20" height="10" fill="#ffffff"/>"##));
+        Ok(())
+    }
+}
@@ -0,0 +1,372 @@
+use crate::{solver, Answer, Part, Solver, SOLVERS};
+use anyhow::{anyhow, Context, Result};
+use std::fmt;
+use std::fs;
+use std::io::{self, Read};
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// The result of solving one part of a puzzle and the wall time it took
+pub struct Timed<T> {
+    pub result: Result<T>,
+    pub elapsed: Duration,
+}
+
+pub fn timed<T>(f: impl FnOnce() -> Result<T>) -> Timed<T> {
+    let start = Instant::now();
+    let result = f();
+    Timed {
+        result,
+        elapsed: start.elapsed(),
+    }
+}
+
+pub struct Run {
+    pub day: usize,
+    pub parse: Duration,
+    pub a: Timed<Answer>,
+    pub b: Timed<Option<Answer>>,
+}
+
+impl Run {
+    pub fn error(&self) -> Option<&anyhow::Error> {
+        self.a
+            .result
+            .as_ref()
+            .err()
+            .or(self.b.result.as_ref().err())
+    }
+
+    pub fn answer(&self, part: Part) -> Result<Option<&Answer>, &anyhow::Error> {
+        match part {
+            Part::A => self.a.result.as_ref().map(Some),
+            Part::B => self.b.result.as_ref().map(Option::as_ref),
+        }
+    }
+}
+
+/// Parse the given input and solve both parts, timing each phase on its own
+pub fn run(solver: &dyn Solver, input: &str) -> Result<Run> {
+    let parsed = timed(|| Ok(solver.parse(input)?));
+    let parse = parsed.elapsed;
+    let parsed = parsed.result?;
+
+    Ok(Run {
+        day: solver.day(),
+        parse,
+        a: timed(|| Ok(parsed.part_one()?)),
+        b: timed(|| Ok(parsed.part_two()?)),
+    })
+}
+
+pub fn read_input(path: &Path) -> Result<String> {
+    fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))
+}
+
+/// Solve both parts of the day's default input
+pub fn run_default(solver: &dyn Solver) -> Result<Run> {
+    let path = solver.default_input();
+    let input = read_input(&path)?;
+    run(solver, &input).with_context(|| format!("Failed to parse {}", path.display()))
+}
+
+/// Solve the day's default input, turning a panic into an error so that one
+/// broken day doesn't stop the others
+fn run_caught(solver: &dyn Solver) -> Result<Run> {
+    panic::catch_unwind(AssertUnwindSafe(|| run_default(solver))).unwrap_or_else(|payload| {
+        let message = payload
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| String::from("unknown reason"));
+        Err(anyhow!("Day {} panicked: {}", solver.day(), message))
+    })
+}
+
+/// Run every solver on its default input using `jobs` worker threads. Runs
+/// are returned in the same order as the solvers.
+pub fn run_parallel(solvers: &[&dyn Solver], jobs: usize) -> Vec<Result<Run>> {
+    let next = AtomicUsize::new(0);
+    let (sender, receiver) = mpsc::channel();
+
+    thread::scope(|scope| {
+        for _ in 0..jobs.clamp(1, solvers.len().max(1)) {
+            let (next, sender) = (&next, sender.clone());
+            scope.spawn(move || {
+                while let Some(solver) = solvers.get(next.fetch_add(1, Ordering::Relaxed)) {
+                    // The receiver outlives every worker, so sending can't fail
+                    let _ = sender.send((solver.day(), run_caught(*solver)));
+                }
+            });
+        }
+    });
+    drop(sender);
+
+    let mut runs = receiver.into_iter().collect::<Vec<_>>();
+    runs.sort_by_key(|(day, _)| *day);
+    runs.into_iter().map(|(_, run)| run).collect()
+}
+
+/// The number of worker threads to use when none is given
+pub fn default_jobs() -> usize {
+    thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1)
+}
+
+/// Where the puzzle input for a single day comes from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Input {
+    File(PathBuf),
+    Stdin,
+    Text(String),
+}
+
+impl Input {
+    /// `-` means stdin, anything else is a path to a file
+    pub fn from_arg(arg: &str) -> Input {
+        match arg {
+            "-" => Input::Stdin,
+            path => Input::File(PathBuf::from(path)),
+        }
+    }
+
+    pub fn read(&self) -> Result<String> {
+        match self {
+            Input::File(path) => read_input(path),
+            Input::Stdin => {
+                let mut input = String::new();
+                io::stdin()
+                    .read_to_string(&mut input)
+                    .context("Failed to read stdin")?;
+                Ok(input)
+            }
+            Input::Text(text) => Ok(text.clone()),
+        }
+    }
+
+    pub fn solve(&self, solver: &dyn Solver, parts: &[Part]) -> Result<Vec<(Part, Answer)>> {
+        solver
+            .solve_parts(&self.read()?, parts)
+            .with_context(|| format!("Failed to solve {}", self))
+    }
+}
+
+impl fmt::Display for Input {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Input::File(path) => write!(f, "{}", path.display()),
+            Input::Stdin => write!(f, "stdin"),
+            Input::Text(_) => write!(f, "inline input"),
+        }
+    }
+}
+
+/// Select the parts to solve using `a`, `b` or `both`
+pub fn parts(spec: &str) -> Result<Vec<Part>> {
+    match spec {
+        "both" => Ok(vec![Part::A, Part::B]),
+        part => Ok(vec![part
+            .parse()
+            .map_err(|_| anyhow!("{:?} is not a, b or both", part))?]),
+    }
+}
+
+fn parse_day(s: &str) -> Result<usize> {
+    match s.parse() {
+        Ok(day @ 1..=25) => Ok(day),
+        Ok(day) => Err(anyhow!("Day {} is not a valid day for advent of code", day)),
+        Err(_) => Err(anyhow!("{:?} is not a valid day", s)),
+    }
+}
+
+/// Select solvers using either a day (`9`), a range of days (`1-10`), `all`
+/// or a comma separated list of those (`1,5,10-12`)
+pub fn select(spec: &str) -> Result<Vec<&'static dyn Solver>> {
+    if spec.contains(',') {
+        let mut solvers = Vec::new();
+        for part in spec.split(',') {
+            solvers.extend(select(part)?);
+        }
+        solvers.sort_by_key(|solver| solver.day());
+        solvers.dedup_by_key(|solver| solver.day());
+        return Ok(solvers);
+    }
+
+    if spec == "all" {
+        return Ok(SOLVERS.to_vec());
+    }
+
+    if let Some((first, last)) = spec.split_once('-') {
+        let (first, last) = (parse_day(first)?, parse_day(last)?);
+        let solvers = SOLVERS
+            .iter()
+            .filter(|solver| (first..=last).contains(&solver.day()))
+            .copied()
+            .collect::<Vec<_>>();
+
+        if solvers.is_empty() {
+            return Err(anyhow!(
+                "No implemented days between {} and {}",
+                first,
+                last
+            ));
+        }
+        return Ok(solvers);
+    }
+
+    let day = parse_day(spec)?;
+    solver(day)
+        .map(|solver| vec![solver])
+        .ok_or_else(|| anyhow!("No implementation for this day yet"))
+}
+
+/// Split several days given as separate arguments (`1 5 10 --jobs 2`) into
+/// a spec for `select` and the flags after them. A single day is not split,
+/// since its next argument can be a path to an input file.
+pub fn positional_days(args: &[String]) -> Option<(String, &[String])> {
+    let count = args
+        .iter()
+        .position(|arg| arg.starts_with("--"))
+        .unwrap_or(args.len());
+    let (days, flags) = args.split_at(count);
+
+    if days.len() < 2 || days.iter().any(|day| day.parse::<usize>().is_err()) {
+        return None;
+    }
+    Some((days.join(","), flags))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Puzzle, Solution};
+
+    fn days(spec: &str) -> Result<Vec<usize>> {
+        Ok(select(spec)?.iter().map(|solver| solver.day()).collect())
+    }
+
+    #[test]
+    fn test_select() -> Result<()> {
+        assert_eq!(days("3")?, vec![3]);
+        assert_eq!(days("2-4")?, vec![2, 3, 4]);
+        assert_eq!(days("all")?.len(), SOLVERS.len());
+        assert!(days("0").is_err());
+        assert!(days("26").is_err());
+        assert!(days("x-4").is_err());
+        assert_eq!(days("9,2-3,3")?, vec![2, 3, 9]);
+        assert!(days("2,").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_positional_days() -> Result<()> {
+        let args = |s: &str| s.split(' ').map(String::from).collect::<Vec<_>>();
+
+        let args1 = args("1 5 10 --jobs 2");
+        let (spec, flags) = positional_days(&args1).unwrap();
+        assert_eq!(spec, "1,5,10");
+        assert_eq!(flags, &args1[3..]);
+        assert_eq!(days(&spec)?, vec![1, 5, 10]);
+
+        assert_eq!(
+            positional_days(&args("3 7")),
+            Some((String::from("3,7"), &[][..]))
+        );
+        assert_eq!(positional_days(&args("9 --part a")), None);
+        assert_eq!(positional_days(&args("9 input.txt")), None);
+        assert_eq!(positional_days(&args("1-3 5")), None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_run_parallel() -> Result<()> {
+        let solvers = select("1-6")?;
+        let runs = run_parallel(&solvers, 4);
+
+        let days = runs
+            .iter()
+            .map(|run| Ok(run.as_ref().map_err(|e| anyhow!("{:#}", e))?.day))
+            .collect::<Result<Vec<_>>>()?;
+        assert_eq!(days, vec![1, 2, 3, 4, 5, 6]);
+        assert!(run_parallel(&[], 4).is_empty());
+        Ok(())
+    }
+
+    struct Panics;
+
+    impl Puzzle for Panics {
+        type Input = ();
+
+        // Borrows day 1's input, so the panic comes from solving it
+        const DAY: usize = 1;
+        const TITLE: &'static str = "Panics";
+
+        fn parse(_: &str) -> crate::error::Result<Self::Input> {
+            Ok(())
+        }
+
+        fn part_one(_: &Self::Input) -> crate::error::Result<Answer> {
+            panic!("index out of bounds")
+        }
+
+        fn part_two(_: &Self::Input) -> crate::error::Result<Option<Answer>> {
+            Ok(None)
+        }
+    }
+
+    #[test]
+    fn test_run_parallel_panic() -> Result<()> {
+        let runs = run_parallel(&[&Panics, solver(2).unwrap()], 2);
+
+        let e = runs[0]
+            .as_ref()
+            .err()
+            .ok_or_else(|| anyhow!("Day 1 did not fail"))?;
+        assert_eq!(e.to_string(), "Day 1 panicked: index out of bounds");
+        assert!(runs[1].is_ok());
+        Ok(())
+    }
+
+    #[test]
+    fn test_parts() -> Result<()> {
+        assert_eq!(parts("a")?, vec![Part::A]);
+        assert_eq!(parts("b")?, vec![Part::B]);
+        assert_eq!(parts("both")?, vec![Part::A, Part::B]);
+        assert!(parts("c").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_input() -> Result<()> {
+        let day7 = solver(7).unwrap();
+        let both = [Part::A, Part::B];
+        let expected = Solution::new(37, Some(168));
+
+        assert_eq!(Input::from_arg("-"), Input::Stdin);
+        assert_eq!(
+            Input::from_arg("data/day7.txt"),
+            Input::File(PathBuf::from("data/day7.txt"))
+        );
+
+        let text = "16,1,2,0,4,2,7,1,2,14";
+        assert_eq!(
+            Input::Text(text.to_string()).solve(day7, &[Part::B])?,
+            vec![(Part::B, Answer::Integer(168))]
+        );
+        assert_eq!(day7.solve_from(&mut text.as_bytes())?, expected);
+
+        let e = Input::Text(String::from("16,x"))
+            .solve(day7, &both)
+            .unwrap_err();
+        assert_eq!(
+            format!("{:#}", e),
+            "Failed to solve inline input: line 1, column 4: invalid digit found in string"
+        );
+        Ok(())
+    }
+}
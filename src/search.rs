@@ -0,0 +1,208 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::hash::Hash;
+
+/// The way a search found from the start to a goal
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Path<S> {
+    /// The total cost of getting to the goal
+    pub cost: usize,
+    /// Every state from the start to the goal, with the cost of getting there
+    pub steps: Vec<(usize, S)>,
+}
+
+impl<S> Path<S> {
+    pub fn states(&self) -> impl Iterator<Item = &S> {
+        self.steps.iter().map(|(_, state)| state)
+    }
+
+    pub fn goal(&self) -> Option<&S> {
+        self.steps.last().map(|(_, state)| state)
+    }
+}
+
+/// States seen by a search, kept in a list so queues only have to hold
+/// indices, with the cheapest known way to get to them
+struct Explored<S> {
+    states: Vec<S>,
+    index: HashMap<S, usize>,
+    costs: Vec<usize>,
+    previous: Vec<Option<usize>>,
+}
+
+impl<S: Clone + Eq + Hash> Explored<S> {
+    fn new(start: S) -> Explored<S> {
+        Explored {
+            states: vec![start.clone()],
+            index: HashMap::from([(start, 0)]),
+            costs: vec![0],
+            previous: vec![None],
+        }
+    }
+
+    /// Record reaching `state` from `from` at `cost`, returning its index if
+    /// that is cheaper than any way found before
+    fn reach(&mut self, state: S, from: usize, cost: usize) -> Option<usize> {
+        let i = match self.index.get(&state) {
+            Some(i) if self.costs[*i] <= cost => return None,
+            Some(i) => *i,
+            None => {
+                self.states.push(state.clone());
+                self.costs.push(cost);
+                self.previous.push(None);
+                self.index.insert(state, self.states.len() - 1);
+                self.states.len() - 1
+            }
+        };
+
+        self.costs[i] = cost;
+        self.previous[i] = Some(from);
+        Some(i)
+    }
+
+    fn path(&self, goal: usize) -> Path<S> {
+        let mut steps = Vec::new();
+        let mut current = Some(goal);
+        while let Some(i) = current {
+            steps.push((self.costs[i], self.states[i].clone()));
+            current = self.previous[i];
+        }
+        steps.reverse();
+
+        Path {
+            cost: self.costs[goal],
+            steps,
+        }
+    }
+}
+
+/// Find the cheapest way from `start` to a state where `goal` holds.
+/// `successors` gives the states one step away with the cost of that step.
+pub fn dijkstra<S, N, I, G>(start: S, successors: N, goal: G) -> Option<Path<S>>
+where
+    S: Clone + Eq + Hash,
+    N: FnMut(&S) -> I,
+    I: IntoIterator<Item = (S, usize)>,
+    G: Fn(&S) -> bool,
+{
+    astar(start, successors, |_| 0, goal)
+}
+
+/// Like `dijkstra`, but tries states that `heuristic` guesses are close to
+/// the goal first. The path is only guaranteed to be the cheapest if the
+/// heuristic never guesses more than the real remaining cost.
+pub fn astar<S, N, I, H, G>(start: S, mut successors: N, heuristic: H, goal: G) -> Option<Path<S>>
+where
+    S: Clone + Eq + Hash,
+    N: FnMut(&S) -> I,
+    I: IntoIterator<Item = (S, usize)>,
+    H: Fn(&S) -> usize,
+    G: Fn(&S) -> bool,
+{
+    let mut queue = BinaryHeap::from([Reverse((heuristic(&start), 0, 0))]);
+    let mut explored = Explored::new(start);
+
+    while let Some(Reverse((_, cost, i))) = queue.pop() {
+        // A cheaper way to this state was found after this one was queued
+        if cost > explored.costs[i] {
+            continue;
+        }
+
+        let state = explored.states[i].clone();
+        if goal(&state) {
+            return Some(explored.path(i));
+        }
+
+        for (next, step) in successors(&state) {
+            let next_cost = cost + step;
+            let guess = next_cost + heuristic(&next);
+            if let Some(j) = explored.reach(next, i, next_cost) {
+                queue.push(Reverse((guess, next_cost, j)));
+            }
+        }
+    }
+
+    None
+}
+
+/// Find the way from `start` to a state where `goal` holds in the fewest
+/// steps, where `successors` gives the states one step away
+pub fn bfs<S, N, I, G>(start: S, mut successors: N, goal: G) -> Option<Path<S>>
+where
+    S: Clone + Eq + Hash,
+    N: FnMut(&S) -> I,
+    I: IntoIterator<Item = S>,
+    G: Fn(&S) -> bool,
+{
+    let mut queue = VecDeque::from([0]);
+    let mut explored = Explored::new(start);
+
+    while let Some(i) = queue.pop_front() {
+        let state = explored.states[i].clone();
+        if goal(&state) {
+            return Some(explored.path(i));
+        }
+
+        for next in successors(&state) {
+            // Every state is first reached by the fewest steps
+            if !explored.index.contains_key(&next) {
+                let j = explored.reach(next, i, explored.costs[i] + 1);
+                queue.extend(j);
+            }
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::{PuzzleError, Result};
+
+    fn found<S>(path: Option<Path<S>>) -> Result<Path<S>> {
+        path.ok_or_else(|| PuzzleError::no_solution("The search found no path"))
+    }
+
+    /// Going from a number to its double costs 1 and to the next number
+    /// costs 2
+    fn successors(n: &usize) -> Vec<(usize, usize)> {
+        vec![(n * 2, 1), (n + 1, 2)]
+    }
+
+    #[test]
+    fn test_dijkstra() -> Result<()> {
+        let path = found(dijkstra(1, successors, |n| *n == 10))?;
+
+        assert_eq!(path.cost, 5);
+        assert_eq!(path.steps, vec![(0, 1), (1, 2), (2, 4), (4, 5), (5, 10)]);
+        assert_eq!(path.goal(), Some(&10));
+        assert!(dijkstra(1, |_| Vec::new(), |n| *n == 10).is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn test_astar() -> Result<()> {
+        // Halving the distance left is a guess that is never too high
+        let path = astar(
+            1,
+            successors,
+            |n| 10usize.saturating_sub(*n) / 2,
+            |n| *n == 10,
+        );
+        assert_eq!(found(path)?.cost, 5);
+        Ok(())
+    }
+
+    #[test]
+    fn test_bfs() -> Result<()> {
+        let path = found(bfs(1, |n| [n * 2, n + 1], |n| *n == 10))?;
+
+        assert_eq!(path.cost, 4);
+        assert_eq!(
+            path.states().copied().collect::<Vec<_>>(),
+            vec![1, 2, 4, 5, 10]
+        );
+        Ok(())
+    }
+}
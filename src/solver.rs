@@ -0,0 +1,225 @@
+use crate::error::{PuzzleError, Result};
+use crate::render::Image;
+use crate::Answer;
+use std::fmt;
+use std::io::Read;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+/// One of the two parts of a puzzle
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Part {
+    A,
+    B,
+}
+
+impl FromStr for Part {
+    type Err = PuzzleError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "a" | "A" => Ok(Part::A),
+            "b" | "B" => Ok(Part::B),
+            _ => Err(PuzzleError::invalid(format!("{:?} is not a part", s))),
+        }
+    }
+}
+
+impl fmt::Display for Part {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Part::A => write!(f, "A"),
+            Part::B => write!(f, "B"),
+        }
+    }
+}
+
+/// The answers to both parts of a puzzle
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Solution {
+    pub a: Answer,
+    pub b: Option<Answer>,
+}
+
+impl Solution {
+    pub fn new<A: Into<Answer>, B: Into<Answer>>(a: A, b: Option<B>) -> Solution {
+        Solution {
+            a: a.into(),
+            b: b.map(Into::into),
+        }
+    }
+
+    pub fn part(&self, part: Part) -> Option<&Answer> {
+        match part {
+            Part::A => Some(&self.a),
+            Part::B => self.b.as_ref(),
+        }
+    }
+}
+
+/// A day's puzzle, split into parsing the input and solving each part using
+/// the parsed model
+pub trait Puzzle: Sync {
+    type Input: 'static;
+
+    const DAY: usize;
+    const TITLE: &'static str;
+
+    fn parse(input: &str) -> Result<Self::Input>;
+
+    fn part_one(input: &Self::Input) -> Result<Answer>;
+
+    fn part_two(input: &Self::Input) -> Result<Option<Answer>>;
+
+    /// A picture of the input or how it was solved, for days where there is
+    /// something to look at
+    fn render(_: &Self::Input) -> Result<Option<Image>> {
+        Ok(None)
+    }
+}
+
+/// Puzzle input that has been parsed by a solver and is ready to be solved
+pub trait Parsed {
+    fn part_one(&self) -> Result<Answer>;
+
+    fn part_two(&self) -> Result<Option<Answer>>;
+
+    fn render(&self) -> Result<Option<Image>>;
+
+    fn part(&self, part: Part) -> Result<Option<Answer>> {
+        match part {
+            Part::A => self.part_one().map(Some),
+            Part::B => self.part_two(),
+        }
+    }
+}
+
+struct ParsedInput<P: Puzzle>(P::Input);
+
+impl<P: Puzzle> Parsed for ParsedInput<P> {
+    fn part_one(&self) -> Result<Answer> {
+        P::part_one(&self.0)
+    }
+
+    fn part_two(&self) -> Result<Option<Answer>> {
+        P::part_two(&self.0)
+    }
+
+    fn render(&self) -> Result<Option<Image>> {
+        P::render(&self.0)
+    }
+}
+
+/// A solution to one day of advent of code
+pub trait Solver: Sync {
+    fn day(&self) -> usize;
+
+    fn title(&self) -> &'static str;
+
+    fn parse(&self, input: &str) -> Result<Box<dyn Parsed>>;
+
+    fn solve(&self, input: &str) -> Result<Solution> {
+        let parsed = self.parse(input)?;
+        Ok(Solution {
+            a: parsed.part_one()?,
+            b: parsed.part_two()?,
+        })
+    }
+
+    /// Solve only the given parts, which is an error when one of them has no
+    /// answer for this day
+    fn solve_parts(&self, input: &str, parts: &[Part]) -> Result<Vec<(Part, Answer)>> {
+        let parsed = self.parse(input)?;
+        parts
+            .iter()
+            .map(|&part| {
+                parsed
+                    .part(part)?
+                    .map(|answer| (part, answer))
+                    .ok_or_else(|| {
+                        PuzzleError::no_solution(format!(
+                            "Day {} has no answer for part {}",
+                            self.day(),
+                            part
+                        ))
+                    })
+            })
+            .collect()
+    }
+
+    /// Read the whole input from `reader` before solving it
+    fn solve_from(&self, reader: &mut dyn Read) -> Result<Solution> {
+        let mut input = String::new();
+        reader.read_to_string(&mut input)?;
+        self.solve(&input)
+    }
+
+    /// Path to the puzzle input that is used when no other input is given
+    fn default_input(&self) -> PathBuf {
+        PathBuf::from(format!("data/day{}.txt", self.day()))
+    }
+}
+
+impl<P: Puzzle + 'static> Solver for P {
+    fn day(&self) -> usize {
+        P::DAY
+    }
+
+    fn title(&self) -> &'static str {
+        P::TITLE
+    }
+
+    fn parse(&self, input: &str) -> Result<Box<dyn Parsed>> {
+        Ok(Box::new(ParsedInput::<P>(P::parse(input)?)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct OnlyA;
+
+    impl Puzzle for OnlyA {
+        type Input = usize;
+
+        const DAY: usize = 26;
+        const TITLE: &'static str = "Only part A";
+
+        fn parse(input: &str) -> Result<Self::Input> {
+            input.trim().parse().map_err(PuzzleError::invalid)
+        }
+
+        fn part_one(input: &Self::Input) -> Result<Answer> {
+            Ok((input * 2).into())
+        }
+
+        fn part_two(_: &Self::Input) -> Result<Option<Answer>> {
+            Ok(None)
+        }
+    }
+
+    #[test]
+    fn test_solve_parts() -> Result<()> {
+        assert_eq!(
+            OnlyA.solve_parts("21", &[Part::A])?,
+            vec![(Part::A, Answer::Integer(42))]
+        );
+        assert_eq!(OnlyA.solve("21")?, Solution::new(42, None::<usize>));
+
+        let e = OnlyA.solve_parts("21", &[Part::A, Part::B]).unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            "no solution: Day 26 has no answer for part B"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_part() -> Result<()> {
+        assert_eq!("a".parse::<Part>()?, Part::A);
+        assert_eq!("B".parse::<Part>()?, Part::B);
+        assert!("c".parse::<Part>().is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,10 @@
+/// The source of a MONAD block for day 24 with the given div, check and
+/// offset
+pub fn monad_block(div: isize, check: isize, offset: isize) -> String {
+    format!(
+        "inp w\nmul x 0\nadd x z\nmod x 26\ndiv z {}\nadd x {}\neql x w\neql x 0\n\
+         mul y 0\nadd y 25\nmul y x\nadd y 1\nmul z y\nmul y 0\nadd y w\nadd y {}\n\
+         mul y x\nadd z y\n",
+        div, check, offset
+    )
+}
@@ -1,151 +1,270 @@
-use anyhow::Result;
+use advent_of_code_2021::day19::{Point3, Rotation};
+use advent_of_code_2021::testing::monad_block;
+use advent_of_code_2021::{manifest, solver, Answer, Manifest, Part, Solution, Solver, SOLVERS};
+use anyhow::{anyhow, Result};
+use std::fs;
 use std::path::Path;
 
-fn run_day<A, B>(day: usize, f: fn(&Path) -> Result<(A, Option<B>)>) -> Result<(A, Option<B>)> {
-    f(format!("data/day{}.txt", day).as_ref())
+fn day(n: usize) -> Result<&'static dyn Solver> {
+    solver(n).ok_or_else(|| anyhow!("Day {} is not registered", n))
 }
 
-#[test]
-fn test_day1() -> Result<()> {
-    assert_eq!(
-        run_day(1, advent_of_code_2021::day1::main)?,
-        (1448, Some(1471))
-    );
-    Ok(())
+fn run_day(n: usize) -> Result<Solution> {
+    let solver = day(n)?;
+    Ok(solver.solve(&fs::read_to_string(solver.default_input())?)?)
 }
 
 #[test]
-fn test_day2() -> Result<()> {
-    assert_eq!(
-        run_day(2, advent_of_code_2021::day2::main)?,
-        (1427868, Some(1568138742))
-    );
+fn test_registry() -> Result<()> {
+    let days = SOLVERS.iter().map(|s| s.day()).collect::<Vec<usize>>();
+    assert_eq!(days, (1..=days.len()).collect::<Vec<usize>>());
     Ok(())
 }
 
 #[test]
-fn test_day3() -> Result<()> {
-    assert_eq!(
-        run_day(3, advent_of_code_2021::day3::main)?,
-        (3882564, Some(3385170))
-    );
+fn test_answers() -> Result<()> {
+    let manifest = Manifest::read(Path::new(manifest::DEFAULT_PATH))?;
+    let mut failures = Vec::new();
+
+    for day in manifest.days() {
+        let solution = run_day(day)?;
+        for part in [Part::A, Part::B] {
+            let expected = manifest.expected(day, part);
+            if expected.is_some() && expected != solution.part(part) {
+                failures.push(format!(
+                    "day {} part {}: expected {:?}, got {:?}",
+                    day,
+                    part,
+                    expected,
+                    solution.part(part)
+                ));
+            }
+        }
+    }
+
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
     Ok(())
 }
 
 #[test]
-fn test_day4() -> Result<()> {
+fn test_day16() -> Result<()> {
+    let day16 = day(16)?;
+    let part =
+        |input: &str, part| -> Result<Option<Answer>> { Ok(day16.parse(input)?.part(part)?) };
+
     assert_eq!(
-        run_day(4, advent_of_code_2021::day4::main)?,
-        (25410, Some(2730))
+        part("8A004A801A8002F478", Part::A)?,
+        Some(Answer::Integer(16))
     );
-    Ok(())
-}
-
-#[test]
-fn test_day5() -> Result<()> {
     assert_eq!(
-        run_day(5, advent_of_code_2021::day5::main)?,
-        (6548, Some(19663))
+        part("620080001611562C8802118E34", Part::A)?,
+        Some(Answer::Integer(12))
+    );
+    assert_eq!(
+        part("C0015000016115A2E0802F182340", Part::A)?,
+        Some(Answer::Integer(23))
+    );
+    assert_eq!(
+        part("A0016C880162017C3686B18A3D4780", Part::A)?,
+        Some(Answer::Integer(31))
     );
-    Ok(())
-}
 
-#[test]
-fn test_day6() -> Result<()> {
+    assert_eq!(part("C200B40A82", Part::B)?, Some(Answer::Integer(3)));
+    assert_eq!(part("04005AC33890", Part::B)?, Some(Answer::Integer(54)));
+    assert_eq!(part("880086C3E88112", Part::B)?, Some(Answer::Integer(7)));
+    assert_eq!(part("CE00C43D881120", Part::B)?, Some(Answer::Integer(9)));
+    assert_eq!(part("D8005AC2A8F0", Part::B)?, Some(Answer::Integer(1)));
+    assert_eq!(part("F600BC2D8F", Part::B)?, Some(Answer::Integer(0)));
+    assert_eq!(part("9C005AC2F8F0", Part::B)?, Some(Answer::Integer(0)));
     assert_eq!(
-        run_day(6, advent_of_code_2021::day6::main)?,
-        (351092, Some(1595330616005))
+        part("9C0141080250320F1802104A08", Part::B)?,
+        Some(Answer::Integer(1))
     );
     Ok(())
 }
 
 #[test]
-fn test_day7() -> Result<()> {
+fn test_day17() -> Result<()> {
+    let day17 = day(17)?;
     assert_eq!(
-        run_day(7, advent_of_code_2021::day7::main)?,
-        (323647, Some(87640209))
+        day17.solve("target area: x=20..30, y=-10..-5")?,
+        Solution::new(45, Some(112))
     );
     Ok(())
 }
 
 #[test]
-fn test_day8() -> Result<()> {
-    assert_eq!(
-        run_day(8, advent_of_code_2021::day8::main)?,
-        (261, Some(987553))
-    );
+fn test_day18() -> Result<()> {
+    let day18 = day(18)?;
+    let homework = "\
+[[[0,[5,8]],[[1,7],[9,6]]],[[4,[1,2]],[[1,4],2]]]
+[[[5,[2,8]],4],[5,[[9,9],0]]]
+[6,[[[6,2],[5,6]],[[7,6],[4,7]]]]
+[[[6,[0,7]],[0,9]],[4,[9,[9,0]]]]
+[[[7,[6,4]],[3,[1,3]]],[[[5,5],1],9]]
+[[6,[[7,3],[3,2]]],[[[3,8],[5,7]],4]]
+[[[[5,4],[7,7]],8],[[8,3],8]]
+[[9,3],[[9,9],[6,[4,9]]]]
+[[2,[[7,7],7]],[[5,8],[[9,3],[0,2]]]]
+[[[[5,2],5],[8,[3,7]]],[[5,[7,5]],[4,4]]]
+";
+    assert_eq!(day18.solve(homework)?, Solution::new(4140, Some(3993)));
     Ok(())
 }
 
 #[test]
-fn test_day9() -> Result<()> {
-    assert_eq!(
-        run_day(9, advent_of_code_2021::day9::main)?,
-        (585, Some(827904))
-    );
+fn test_day19() -> Result<()> {
+    // Scanner k sees beacon groups k and k + 1, so neighbouring scanners
+    // share 13 beacons. Every scanner is turned by a different rotation.
+    let mut seed: u64 = 19;
+    let mut random = |range: isize| {
+        seed = seed
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (seed >> 33) as isize % (2 * range + 1) - range
+    };
+
+    let groups = (0..5)
+        .map(|_| {
+            (0..13)
+                .map(|_| Point3::new(random(1000), random(1000), random(1000)))
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    let positions = (0..4)
+        .map(|k| {
+            if k == 0 {
+                Point3::default()
+            } else {
+                Point3::new(random(1000), random(1000), random(1000))
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let rotations = Rotation::all();
+    let mut input = String::new();
+    for (k, position) in positions.iter().enumerate() {
+        input.push_str(&format!("--- scanner {} ---\n", k));
+        for beacon in groups[k].iter().chain(&groups[k + 1]) {
+            let local = rotations[k * 5].apply(*beacon - *position);
+            input.push_str(&format!("{},{},{}\n", local.x, local.y, local.z));
+        }
+        input.push('\n');
+    }
+
+    let largest = positions
+        .iter()
+        .flat_map(|a| positions.iter().map(move |b| a.manhattan(b)))
+        .max();
+
+    let day19 = day(19)?;
+    assert_eq!(day19.solve(&input)?, Solution::new(5 * 13, largest));
     Ok(())
 }
 
 #[test]
-fn test_day10() -> Result<()> {
-    assert_eq!(
-        run_day(10, advent_of_code_2021::day10::main)?,
-        (339477, Some(3049320156))
-    );
+fn test_day20() -> Result<()> {
+    // Every pixel keeps the value of the centre of its square, so the image
+    // never changes
+    let algorithm = (0..512)
+        .map(|i| if i & 16 != 0 { '#' } else { '.' })
+        .collect::<String>();
+    let input = format!("{}\n\n#..#.\n#....\n##..#\n..#..\n..###\n", algorithm);
+
+    let day20 = day(20)?;
+    assert_eq!(day20.solve(&input)?, Solution::new(10, Some(10)));
     Ok(())
 }
 
 #[test]
-fn test_day11() -> Result<()> {
+fn test_day21() -> Result<()> {
+    let day21 = day(21)?;
+    let input = "Player 1 starting position: 4\nPlayer 2 starting position: 8\n";
     assert_eq!(
-        run_day(11, advent_of_code_2021::day11::main)?,
-        (1655, Some(337))
+        day21.solve(input)?,
+        Solution::new(739785, Some(444356092776315))
     );
     Ok(())
 }
 
 #[test]
-fn test_day12() -> Result<()> {
-    assert_eq!(
-        run_day(12, advent_of_code_2021::day12::main)?,
-        (3292, Some(89592))
-    );
+fn test_day22() -> Result<()> {
+    let day22 = day(22)?;
+    let steps = "\
+on x=10..12,y=10..12,z=10..12
+on x=11..13,y=11..13,z=11..13
+off x=9..11,y=9..11,z=9..11
+on x=10..10,y=10..10,z=10..10
+on x=-100..100,y=0..0,z=0..0
+";
+    // The last step lights 201 cubes, of which 101 are within -50..50
+    assert_eq!(day22.solve(steps)?, Solution::new(39 + 101, Some(39 + 201)));
     Ok(())
 }
 
 #[test]
-fn test_day13() -> Result<()> {
-    assert_eq!(
-        run_day(13, advent_of_code_2021::day13::main)?,
-        (
-            842,
-            Some(String::from(
-                "###__####_#__#_###___##____##_####_#__#\n\
-                     #__#_#____#_#__#__#_#__#____#____#_#__#\n\
-                     ###__###__##___#__#_#_______#___#__#__#\n\
-                     #__#_#____#_#__###__#_______#__#___#__#\n\
-                     #__#_#____#_#__#_#__#__#_#__#_#____#__#\n\
-                     ###__#____#__#_#__#__##___##__####__##_\n"
-            ))
-        )
-    );
+fn test_day23() -> Result<()> {
+    let day23 = day(23)?;
+    let burrow = "\
+#############
+#...........#
+###B#C#B#D###
+  #A#D#C#A#
+  #########
+";
+    assert_eq!(day23.solve(burrow)?, Solution::new(12521, Some(44169)));
     Ok(())
 }
 
 #[test]
-fn test_day14() -> Result<()> {
+fn test_day24() -> Result<()> {
+    // Pushing blocks have a check of at least 10, so the digit never matches
+    // and the offset is pushed. Popping blocks pair up with them like
+    // brackets: (()) () ((())) ()
+    let blocks: [(isize, isize, isize); 14] = [
+        (1, 11, 5),
+        (1, 11, 2),
+        (26, -2, 6),
+        (26, -8, 6),
+        (1, 11, 7),
+        (26, -1, 6),
+        (1, 11, 1),
+        (1, 11, 12),
+        (1, 11, 3),
+        (26, -3, 6),
+        (26, -4, 6),
+        (26, -9, 6),
+        (1, 11, 4),
+        (26, -10, 6),
+    ];
+    let monad = blocks
+        .iter()
+        .map(|(div, check, offset)| monad_block(*div, *check, *offset))
+        .collect::<String>();
+
+    let day24 = day(24)?;
     assert_eq!(
-        run_day(14, advent_of_code_2021::day14::main)?,
-        (2967, Some(3692219987038))
+        day24.solve(&monad)?,
+        Solution::new(99963991999193, Some(41111791119171))
     );
     Ok(())
 }
 
 #[test]
-fn test_day15() -> Result<()> {
-    assert_eq!(
-        run_day(15, advent_of_code_2021::day15::main)?,
-        (720, Some(3025))
-    );
+fn test_day25() -> Result<()> {
+    let day25 = day(25)?;
+    let floor = "\
+v...>>.vv>
+.vv>>.vv..
+>>.>v>...v
+>>v>>.>.v.
+v>v.vv.v..
+>.>>..v...
+.vv..>.>v.
+v.v..>>v.v
+....v..v.>
+";
+    // The last day only has one puzzle
+    assert_eq!(day25.solve(floor)?, Solution::new(58, None::<usize>));
     Ok(())
 }